@@ -0,0 +1,65 @@
+//! Measures the time and the number of allocations of [CsvReader::read] on a large
+//! document. Run with `cargo bench`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use rust_csv_reader::{CsvReader, DefaultSchema};
+
+/// Counts the allocations and reallocations, to check that the result is not grown
+/// row by row.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static REALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const ROWS: usize = 1_000_000;
+
+fn main() {
+    let mut csv = String::from("name,height,weight\n");
+    for i in 0..ROWS {
+        csv.push_str(&format!("name{},{}.5,{}\n", i, i % 200, i % 90));
+    }
+
+    let reader = CsvReader::<DefaultSchema>::default();
+
+    ALLOCATIONS.store(0, Ordering::Relaxed);
+    REALLOCATIONS.store(0, Ordering::Relaxed);
+    let start = Instant::now();
+
+    let rows = reader.read(csv.as_bytes()).unwrap();
+
+    let elapsed = start.elapsed();
+    assert_eq!(ROWS, rows.len());
+
+    println!(
+        "read {} rows in {:?}: {} allocations, {} reallocations, capacity {}",
+        rows.len(),
+        elapsed,
+        ALLOCATIONS.load(Ordering::Relaxed),
+        REALLOCATIONS.load(Ordering::Relaxed),
+        rows.capacity()
+    );
+}
@@ -1,7 +1,19 @@
-use std::path::Path;
+use std::{error::Error, path::Path};
 
-use clap::Parser;
-use rust_csv_reader::{CsvReader, DefaultSchema};
+use clap::{Parser, ValueEnum};
+use rust_csv_reader::{print_table, CsvReader, DefaultSchema};
+
+/// How to print the document.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    /// The number of rows and columns.
+    Stats,
+    /// The rows as a table.
+    Table,
+    /// The rows as a JSON array, of objects keyed by column name.
+    #[cfg(feature = "serde")]
+    Json,
+}
 
 /// CLI arguments.
 #[derive(Parser, Debug)]
@@ -13,9 +25,17 @@ struct Args {
     /// Don't print anything.
     #[arg(short, long)]
     quiet: bool,
+
+    /// Print the rows as a table, same as `--format table`.
+    #[arg(short, long)]
+    table: bool,
+
+    /// How to print the document.
+    #[arg(short, long, value_enum, default_value_t = Format::Stats)]
+    format: Format,
 }
 
-fn print_stats(path: &Path, rows: &Vec<DefaultSchema>) {
+fn print_stats(path: &Path, rows: &[DefaultSchema]) {
     println!("{}:", path.to_str().unwrap());
     if rows.is_empty() {
         println!("no rows");
@@ -24,16 +44,53 @@ fn print_stats(path: &Path, rows: &Vec<DefaultSchema>) {
     }
 }
 
+fn preview(reader: &CsvReader<DefaultSchema>, path: &Path) -> Result<(), Box<dyn Error>> {
+    let data = std::fs::read(path)?;
+
+    let rows = reader.read(&data)?;
+    print_table(&rows, &reader.read_headers(&data));
+
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+fn print_json(reader: &CsvReader<DefaultSchema>, path: &Path) -> Result<(), Box<dyn Error>> {
+    let data = std::fs::read(path)?;
+
+    let rows = reader.read(&data)?;
+    let headers = reader.read_headers(&data);
+    let headers = reader.context().has_header().then_some(headers.as_slice());
+    println!("{}", rust_csv_reader::to_json(&rows, headers));
+
+    Ok(())
+}
+
 fn main() {
     let args = Args::parse();
 
     let path = Path::new(&args.input);
     let reader = CsvReader::<DefaultSchema>::with_default_schema();
+    let format = if args.table {
+        Format::Table
+    } else {
+        args.format
+    };
+
+    if args.quiet {
+        if let Err(e) = reader.read_file(path) {
+            eprintln!("{}", e);
+        }
+        return;
+    }
 
-    let result = reader.read_file(path);
+    let result = match format {
+        Format::Stats => reader.read_file(path).map(|v| print_stats(path, &v)),
+        Format::Table => preview(&reader, path),
+        #[cfg(feature = "serde")]
+        Format::Json => print_json(&reader, path),
+    };
 
-    match result {
-        Ok(v) => print_stats(path, &v),
-        Err(e) => eprintln!("{}", e)
+    if let Err(e) = result {
+        eprintln!("{}", e)
     }
-}
\ No newline at end of file
+}
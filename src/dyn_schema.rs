@@ -0,0 +1,207 @@
+use std::error::Error;
+
+use crate::{
+    header,
+    parser::{ColumnType, ParseContext, ParseError, RowSpan, RowSpanIterator},
+    CsvReader, DefaultSchema,
+};
+
+/// The columns of a document and the type of each column, known at runtime only.
+///
+/// The [crate::schema] macro needs the columns at compile time. When they are only known
+/// when reading the document, a `DynSchema` can be inferred from the header and the first
+/// rows with [DynSchema::from_sample], then used to parse every row with the type of its
+/// column with [CsvReader::read_with_dyn_schema]. Unlike the [crate::parser::DefaultRowParser],
+/// a value that does not match the type of its column is an error, instead of a value of
+/// another type.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DynSchema {
+    columns: Vec<(String, ColumnType)>,
+}
+
+impl DynSchema {
+    pub fn new(columns: Vec<(String, ColumnType)>) -> Self {
+        Self { columns }
+    }
+
+    /// Infers the type of each column of the header from the first `sample_rows` rows,
+    /// with the default [ParseContext].
+    pub fn from_sample(span: &[u8], sample_rows: usize) -> Self {
+        Self::from_sample_with_context(span, sample_rows, &ParseContext::default())
+    }
+
+    /// Infers the type of each column of the header from the first `sample_rows` rows.
+    /// The type of a column is the first type of the [crate::parser::TypeInference] of the
+    /// context that can parse every non-empty value of the column in the sample, or
+    /// [ColumnType::String] if none can or if every value is empty.
+    pub fn from_sample_with_context(
+        span: &[u8],
+        sample_rows: usize,
+        context: &ParseContext,
+    ) -> Self {
+        let span = &*crate::decode(span);
+        let context = context.for_document(span);
+        let names =
+            header::header_names(CsvReader::<DefaultSchema>::header(span, &context), &context);
+        // The types that can parse every non-empty value seen so far, for each column.
+        let inferred: Vec<ColumnType> = context.inferred_types().copied().collect();
+        let mut candidates = vec![inferred; names.len()];
+        let mut seen = vec![false; names.len()];
+
+        for (_, row) in CsvReader::<DefaultSchema>::rows(span, &context).take(sample_rows) {
+            let fields = RowSpanIterator::new(&context, row).take(names.len());
+            for (column, value) in fields.enumerate() {
+                if context.is_empty_field(&value) {
+                    continue;
+                }
+                seen[column] = true;
+                candidates[column].retain(|t| t.parse(&value, &context).is_ok());
+            }
+        }
+
+        let columns = names
+            .into_iter()
+            .zip(candidates.iter().zip(&seen))
+            .map(|(name, (types, seen))| {
+                let column_type = types
+                    .first()
+                    .filter(|_| *seen)
+                    .copied()
+                    .unwrap_or(ColumnType::String);
+
+                (name, column_type)
+            })
+            .collect();
+
+        Self { columns }
+    }
+
+    /// The name and the type of each column.
+    pub fn columns(&self) -> &[(String, ColumnType)] {
+        &self.columns
+    }
+
+    /// Parses each field of the row with the type of its column. The fields after the
+    /// last column are ignored, and the columns missing from the row are empty.
+    pub(crate) fn parse_row(
+        &self,
+        row: &RowSpan,
+        context: &ParseContext,
+    ) -> Result<DefaultSchema, ParseError> {
+        let mut iterator = RowSpanIterator::new(context, row);
+        let mut fields = Vec::with_capacity(self.columns.len());
+
+        for (column, (_, column_type)) in self.columns.iter().enumerate() {
+            let value = match iterator.next() {
+                Some(span) if !context.is_empty_field(&span) => {
+                    let value = column_type.parse(&span, context).map_err(|e| {
+                        ParseError::InvalidField {
+                            line: None,
+                            column,
+                            message: e.to_string(),
+                        }
+                    })?;
+                    Some(value)
+                }
+                _ => None,
+            };
+            fields.push(value);
+        }
+
+        Ok(DefaultSchema::new(fields))
+    }
+}
+
+impl CsvReader<DefaultSchema> {
+    /// Reads the rows of the document, each field parsed as the type of its column in the
+    /// [DynSchema]. Returns [ParseError::InvalidField] for the first value that does not
+    /// match the type of its column.
+    pub fn read_with_dyn_schema(
+        &self,
+        span: &[u8],
+        schema: &DynSchema,
+    ) -> Result<Vec<DefaultSchema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+
+        Self::rows(span, &context)
+            .map(|(number, row)| {
+                schema
+                    .parse_row(row, &context)
+                    .map_err(|e| e.at_line(number).into())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        dyn_schema::DynSchema,
+        parser::{ColumnType, FieldValue, ParseError},
+        CsvReader, DefaultSchema,
+    };
+
+    #[test]
+    fn infers_then_parses_each_column_with_its_type() {
+        let csv = b"id,score,name,empty\n1,2,foo,\n2,2.5,bar,\n3,1e1,7,\n";
+
+        let schema = DynSchema::from_sample(csv, 2);
+
+        assert_eq!(
+            &[
+                ("id".to_string(), ColumnType::Int),
+                ("score".to_string(), ColumnType::Float),
+                ("name".to_string(), ColumnType::String),
+                ("empty".to_string(), ColumnType::String),
+            ],
+            schema.columns()
+        );
+
+        let rows = CsvReader::<DefaultSchema>::default()
+            .read_with_dyn_schema(csv, &schema)
+            .unwrap();
+
+        assert_eq!(
+            &vec![
+                Some(FieldValue::Int(3)),
+                Some(FieldValue::Float(10.0)),
+                Some(FieldValue::String("7".into())),
+                None,
+            ],
+            rows[2].fields()
+        );
+        assert_eq!(Some(FieldValue::Float(2.0)), rows[0].fields()[1]);
+    }
+
+    #[test]
+    fn rows_of_another_width_are_sampled() {
+        let schema = DynSchema::from_sample(b"a,b\n1\n2,x,9\n", 10);
+
+        assert_eq!(
+            &[
+                ("a".to_string(), ColumnType::Int),
+                ("b".to_string(), ColumnType::String),
+            ],
+            schema.columns()
+        );
+    }
+
+    #[test]
+    fn value_of_another_type_is_an_error() {
+        let schema = DynSchema::new(vec![("id".into(), ColumnType::Int)]);
+
+        let err = CsvReader::<DefaultSchema>::default()
+            .read_with_dyn_schema(b"id\n1\nx\n", &schema)
+            .unwrap_err();
+
+        assert!(matches!(
+            err.downcast_ref::<ParseError>(),
+            Some(ParseError::InvalidField {
+                line: Some(3),
+                column: 0,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use std::borrow::Cow;
+
+const UTF16_LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF16_BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+/// The encoding of a document, as detected from its byte order mark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// No UTF-16 byte order mark. The document is read as is.
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+}
+
+/// Detects the encoding of the document from its first bytes.
+pub fn detect(span: &[u8]) -> Encoding {
+    if span.starts_with(&UTF16_LE_BOM) {
+        Encoding::Utf16Le
+    } else if span.starts_with(&UTF16_BE_BOM) {
+        Encoding::Utf16Be
+    } else {
+        Encoding::Utf8
+    }
+}
+
+/// Transcodes a UTF-16 document to UTF-8, without its byte order mark, so that it can be
+/// parsed like any other document. Invalid code units are replaced by `U+FFFD`, and a
+/// trailing odd byte is dropped. A UTF-8 document is borrowed unchanged.
+pub fn decode(span: &[u8]) -> Cow<'_, [u8]> {
+    let from_bytes = match detect(span) {
+        Encoding::Utf8 => return Cow::Borrowed(span),
+        Encoding::Utf16Le => u16::from_le_bytes,
+        Encoding::Utf16Be => u16::from_be_bytes,
+    };
+
+    let units: Vec<u16> = span[2..]
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+
+    Cow::Owned(String::from_utf16_lossy(&units).into_bytes())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        encoding::{decode, detect, Encoding},
+        parser::FieldValue,
+        CsvReader, DefaultSchema,
+    };
+
+    fn utf16_le(text: &str) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend(text.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn detect_returns_encoding_of_bom() {
+        assert_eq!(Encoding::Utf16Le, detect(b"\xFF\xFEa\x00"));
+        assert_eq!(Encoding::Utf16Be, detect(b"\xFE\xFF\x00a"));
+        assert_eq!(Encoding::Utf8, detect(b"a,b\n"));
+    }
+
+    #[test]
+    fn decode_utf16_be() {
+        assert_eq!(
+            b"\xC3\xA9,1".as_slice(),
+            &*decode(b"\xFE\xFF\x00\xE9\x00,\x001")
+        );
+    }
+
+    #[test]
+    fn read_utf16_le_document() {
+        let csv = utf16_le("name,height\r\nRenée,1.7\r\n");
+
+        let rows = CsvReader::<DefaultSchema>::default().read(&csv).unwrap();
+
+        assert_eq!(1, rows.len());
+        assert_eq!(
+            &vec![
+                Some(FieldValue::String("Renée".into())),
+                Some(FieldValue::Float(1.7))
+            ],
+            rows[0].fields()
+        );
+    }
+
+    #[test]
+    fn every_method_of_the_reader_transcodes() {
+        let csv = utf16_le("name,height\r\nRenée,1.7\r\nfoo,2\r\n");
+        let reader = CsvReader::<DefaultSchema>::default();
+
+        assert_eq!(vec!["name", "height"], reader.read_headers(&csv));
+        assert_eq!(Some(1), reader.header_index(&csv).index_of("height"));
+        assert_eq!(2, reader.count_rows(&csv));
+        assert!(reader.validate_report(&csv).is_ok());
+        assert_eq!(
+            vec![vec!["Renée", "1.7"], vec!["foo", "2"]],
+            reader.read_str_fields(&csv).unwrap()
+        );
+
+        let rows: Vec<_> = reader.read_iter(&csv).map(Result::unwrap).collect();
+        assert_eq!(reader.read(&csv).unwrap(), rows);
+    }
+}
@@ -0,0 +1,373 @@
+use std::{collections::HashMap, error::Error};
+
+use crate::{
+    parser::{ParseContext, RowSpan, RowSpanIterator},
+    RowIterator,
+};
+
+/// A schema whose fields are expected to be the columns of the header.
+/// Implemented by the [crate::schema] macro.
+pub trait ExpectedHeaders {
+    /// The names of the columns, in the order of the schema fields.
+    fn expected_headers() -> &'static [&'static str];
+}
+
+/// How the header of a document is compared to the expected headers.
+/// By default, the names must match exactly and appear in the same order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeaderComparison {
+    pub ignore_order: bool,
+    pub ignore_case: bool,
+}
+
+impl HeaderComparison {
+    fn eq(&self, a: &str, b: &str) -> bool {
+        if self.ignore_case {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    }
+
+    /// Compares the actual header to the expected one.
+    pub fn compare(&self, actual: &[String], expected: &[&str]) -> Result<(), HeaderMismatch> {
+        let missing: Vec<String> = expected
+            .iter()
+            .filter(|e| !actual.iter().any(|a| self.eq(a, e)))
+            .map(|e| e.to_string())
+            .collect();
+
+        let extra: Vec<String> = actual
+            .iter()
+            .filter(|a| !expected.iter().any(|e| self.eq(a, e)))
+            .cloned()
+            .collect();
+
+        let misordered = !self.ignore_order
+            && missing.is_empty()
+            && extra.is_empty()
+            && !actual.iter().zip(expected).all(|(a, e)| self.eq(a, e));
+
+        if missing.is_empty() && extra.is_empty() && !misordered {
+            Ok(())
+        } else {
+            Err(HeaderMismatch {
+                missing,
+                extra,
+                misordered,
+            })
+        }
+    }
+}
+
+/// The differences between the header of a document and the expected headers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderMismatch {
+    /// The expected columns that are not in the header.
+    pub missing: Vec<String>,
+    /// The columns of the header that are not expected.
+    pub extra: Vec<String>,
+    /// Whether the columns are the expected ones, but in a different order.
+    pub misordered: bool,
+}
+
+impl std::fmt::Display for HeaderMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "header mismatch:")?;
+        if !self.missing.is_empty() {
+            write!(f, " missing columns {:?}", self.missing)?;
+        }
+        if !self.extra.is_empty() {
+            write!(f, " extra columns {:?}", self.extra)?;
+        }
+        if self.misordered {
+            write!(f, " columns are not in the expected order")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for HeaderMismatch {}
+
+/// The columns of a header, to find a column by its name.
+///
+/// An index can be built once and reused to read several documents with the same
+/// layout, see [crate::CsvReader::read_with_index].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderIndex {
+    raw: Vec<u8>,
+    names: Vec<String>,
+    positions: HashMap<String, usize>,
+}
+
+impl HeaderIndex {
+    /// Builds the index of the header row.
+    pub fn new(header: &RowSpan, context: &ParseContext) -> Self {
+        let names = header_names(header, context);
+
+        let mut positions = HashMap::new();
+        for (i, name) in names.iter().enumerate() {
+            // With duplicate names, the first column wins.
+            positions.entry(name.clone()).or_insert(i);
+        }
+
+        Self {
+            raw: header.to_vec(),
+            names,
+            positions,
+        }
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Returns the 0-based index of the column with this name.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.positions.get(name).copied()
+    }
+
+    /// Returns the 0-based index of the first column with this name, ignoring the ASCII
+    /// case, such as `Name` for `name`. [HeaderIndex::index_of] is case-sensitive.
+    pub fn index_of_ignore_case(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns true if the header row is the one this index was built from.
+    pub fn matches(&self, header: &RowSpan) -> bool {
+        self.raw == header
+    }
+}
+
+/// The columns of a document in their original order, and the column of each field of a
+/// schema, so that rows read by name can be written back in the original column order.
+/// See [crate::CsvReader::read_by_name] and [crate::CsvWriter::write_mapped].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnOrder {
+    names: Vec<String>,
+    positions: Vec<Option<usize>>,
+}
+
+impl ColumnOrder {
+    /// Maps each field to the first column with the same name, if any.
+    pub fn new(names: Vec<String>, fields: &[&str]) -> Self {
+        let positions = fields
+            .iter()
+            .map(|field| names.iter().position(|name| name == field))
+            .collect();
+
+        Self { names, positions }
+    }
+
+    /// The names of the columns, in the original order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The 0-based column of each field, `None` if the document has no such column.
+    pub fn positions(&self) -> &[Option<usize>] {
+        &self.positions
+    }
+
+    /// Returns the raw values of the columns without a field, in the original order.
+    pub(crate) fn unmapped_fields(&self, row: &RowSpan, context: &ParseContext) -> Vec<String> {
+        RowSpanIterator::new(context, row)
+            .enumerate()
+            .filter(|(i, _)| !self.positions.contains(&Some(*i)))
+            .map(|(_, field)| String::from_utf8_lossy(&field).into_owned())
+            .collect()
+    }
+
+    /// Moves the values given in field order to their column, in the original order.
+    /// The columns without a field are empty.
+    pub fn to_column_order<T>(&self, fields: Vec<T>) -> Vec<Option<T>> {
+        let mut columns: Vec<Option<T>> = (0..self.names.len()).map(|_| None).collect();
+
+        for (field, position) in fields.into_iter().zip(&self.positions) {
+            if let Some(column) = position.and_then(|p| columns.get_mut(p)) {
+                *column = Some(field);
+            }
+        }
+
+        columns
+    }
+}
+
+/// A row read by [crate::CsvReader::read_by_name], with the raw values of the columns
+/// that are not fields of the schema, to write them back unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MappedRow<Schema> {
+    pub row: Schema,
+    /// The values of the columns without a field, in the original order of the columns.
+    pub unmapped: Vec<String>,
+}
+
+/// Splits the header row into column names.
+///
+/// A header of several rows, as set by [ParseContext::header_rows], has composite names:
+/// the names of each row joined with the header separator, such as `Sales / Q1`. Empty
+/// names are skipped, and an empty name in the upper rows repeats the name on its left,
+/// as spreadsheets do with merged cells.
+///
+/// The names are split with the [ParseContext::header_delimiter], if any.
+pub(crate) fn header_names(header: &RowSpan, context: &ParseContext) -> Vec<String> {
+    let context = &*context.for_header();
+    let split = |row: &RowSpan| -> Vec<String> {
+        RowSpanIterator::new(context, row)
+            .map(|name| String::from_utf8_lossy(&name).into_owned())
+            .collect()
+    };
+
+    if context.header_rows() <= 1 {
+        return split(header);
+    }
+
+    // Each row is split once, and the shorter rows are padded with empty names.
+    let rows: Vec<Vec<String>> = RowIterator::new(header, context).map(split).collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let last = rows.len().saturating_sub(1);
+
+    let mut filled: Vec<Vec<&str>> = Vec::with_capacity(rows.len());
+    for (r, row) in rows.iter().enumerate() {
+        let mut names = Vec::with_capacity(width);
+        for i in 0..width {
+            let name = row.get(i).map(String::as_str).unwrap_or_default();
+            let name = match (name.is_empty(), names.last()) {
+                (true, Some(previous)) if r < last => *previous,
+                _ => name,
+            };
+            names.push(name);
+        }
+        filled.push(names);
+    }
+
+    (0..width)
+        .map(|i| {
+            filled
+                .iter()
+                .map(|names| names[i])
+                .filter(|name| !name.is_empty())
+                .collect::<Vec<_>>()
+                .join(context.header_separator())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        header::{HeaderComparison, HeaderIndex, HeaderMismatch},
+        parser::{FieldValue, ParseContext},
+        CsvReader, DefaultSchema,
+    };
+
+    #[test]
+    fn header_delimiter_differs_from_data_delimiter() {
+        let csv = b"name\theight\nfoo,1.5\n";
+        let context = ParseContext::builder()
+            .header_delimiter(Some(b'\t'))
+            .build()
+            .unwrap();
+        let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+        assert_eq!(vec!["name", "height"], reader.read_headers(csv));
+        assert_eq!(
+            &vec![
+                Some(FieldValue::String("foo".into())),
+                Some(FieldValue::Float(1.5))
+            ],
+            reader.read(csv).unwrap()[0].fields()
+        );
+    }
+
+    #[test]
+    fn header_index_of() {
+        let index = HeaderIndex::new(b"name,age,name", &ParseContext::default());
+
+        assert_eq!(3, index.len());
+        assert_eq!(Some(0), index.index_of("name"));
+        assert_eq!(Some(1), index.index_of("age"));
+        assert_eq!(None, index.index_of("height"));
+    }
+
+    #[test]
+    fn header_index_is_case_sensitive() {
+        let index = HeaderIndex::new(b"Name,AGE,name", &ParseContext::default());
+
+        assert_eq!(Some(0), index.index_of("Name"));
+        assert_eq!(Some(2), index.index_of("name"));
+        assert_eq!(None, index.index_of("age"));
+        assert_eq!(Some(1), index.index_of_ignore_case("age"));
+        assert_eq!(Some(0), index.index_of_ignore_case("NAME"));
+    }
+
+    #[test]
+    fn header_index_matches() {
+        let index = HeaderIndex::new(b"name,age", &ParseContext::default());
+
+        assert!(index.matches(b"name,age"));
+        assert!(!index.matches(b"name,height"));
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn exact_match_returns_ok() {
+        let result = HeaderComparison::default().compare(&names(&["a", "b"]), &["a", "b"]);
+
+        assert_eq!(Ok(()), result);
+    }
+
+    #[test]
+    fn reports_missing_and_extra_columns() {
+        let result = HeaderComparison::default().compare(&names(&["a", "c"]), &["a", "b"]);
+
+        assert_eq!(
+            Err(HeaderMismatch {
+                missing: names(&["b"]),
+                extra: names(&["c"]),
+                misordered: false,
+            }),
+            result
+        );
+    }
+
+    #[test]
+    fn different_order_returns_err_unless_ignored() {
+        let actual = names(&["b", "a"]);
+
+        let strict = HeaderComparison::default().compare(&actual, &["a", "b"]);
+        assert!(strict.unwrap_err().misordered);
+
+        let comparison = HeaderComparison {
+            ignore_order: true,
+            ..Default::default()
+        };
+        assert_eq!(Ok(()), comparison.compare(&actual, &["a", "b"]));
+    }
+
+    #[test]
+    fn different_case_returns_err_unless_ignored() {
+        let actual = names(&["Name"]);
+
+        assert!(HeaderComparison::default()
+            .compare(&actual, &["name"])
+            .is_err());
+
+        let comparison = HeaderComparison {
+            ignore_case: true,
+            ..Default::default()
+        };
+        assert_eq!(Ok(()), comparison.compare(&actual, &["name"]));
+    }
+}
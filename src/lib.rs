@@ -1,191 +1,3931 @@
-use std::{error::Error, fs::File, marker::PhantomData, path::Path};
+use std::{
+    borrow::{BorrowMut, Cow},
+    collections::HashMap,
+    error::Error,
+    fs::File,
+    hash::Hash,
+    io::{BufReader, Read},
+    marker::PhantomData,
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+#[cfg(feature = "flate2")]
+use std::io::BufRead;
 
 use memmap::MmapOptions;
-use parser::{FieldValue, IntoRowParser, ParseContext, RowSpan};
+use parser::{
+    BorrowedRowParser, ColumnType, CommentMode, ConfigError, DefaultRowParser, FieldParser,
+    FieldValue, IntoBorrowedRowParser, IntoFieldParser, IntoRowParser, MappedRowParser,
+    ParseContext, ParseError, RowSpan, RowSpanIterator, StrictRowParser, TupleRow,
+};
 
+pub mod dyn_schema;
+#[cfg(feature = "encoding")]
+pub mod encoding;
+pub mod header;
 pub mod parser;
+pub mod profile;
+pub mod report;
+mod sample;
+pub mod signature;
+pub mod spec;
+pub mod splitter;
+pub mod table;
+pub mod writer;
+
+pub use dyn_schema::DynSchema;
+pub use header::{
+    ColumnOrder, ExpectedHeaders, HeaderComparison, HeaderIndex, HeaderMismatch, MappedRow,
+};
+pub use profile::ColumnProfile;
+pub use report::{ValidationError, ValidationErrorKind, ValidationReport};
+pub use signature::schema_signature;
+pub use spec::{Spec, SpecError};
+pub use splitter::{RecordSplitter, TerminatorSplitter};
+pub use table::{print_table, Table};
+pub use writer::{CsvWriter, LineTerminator, QuoteStyle};
 
 use parser::RowParser;
 
 pub const NEWLINE: u8 = 0x0A;
 pub const COMMA: u8 = 0x2C;
 
+/// The number of rows used to estimate the number of rows of a document.
+const ROW_COUNT_SAMPLE: usize = 16;
+
+/// The most rows allocated upfront from the estimated number of rows of a document.
+const MAX_ESTIMATED_ROWS: usize = 1 << 16;
+
+/// The number of rows read between two checks of the cancellation flag.
+const CANCELLATION_CHECK_ROWS: usize = 1024;
+
+/// The extensions accepted by [CsvReader::read_file_checked].
+#[cfg(not(feature = "flate2"))]
+const FILE_EXTENSIONS: &[&str] = &["csv", "tsv"];
+#[cfg(feature = "flate2")]
+const FILE_EXTENSIONS: &[&str] = &["csv", "tsv", "gz"];
+
+/// The most bytes allocated upfront for a member of a zip archive, whatever the size the
+/// archive declares, see [CsvReader::read_zip_member].
+#[cfg(feature = "zip")]
+const ZIP_INITIAL_CAPACITY: usize = 1 << 20;
+
+/// The first bytes of a gzip stream.
+#[cfg(feature = "flate2")]
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// The row kept among the rows with the same key, see [CsvReader::read_dedup_by_key].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+    First,
+    Last,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct DefaultSchema {
     fields: Vec<Option<FieldValue>>,
 }
 
-impl<'a> DefaultSchema {
-    pub fn fields(&'a self) -> &'a Vec<Option<FieldValue>> {
+impl DefaultSchema {
+    pub fn fields(&self) -> &[Option<FieldValue>] {
         &self.fields
     }
 
+    /// The number of columns of the row, including the empty fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
     pub fn new(fields: Vec<Option<FieldValue>>) -> Self {
         Self { fields }
     }
+
+    /// Returns the value of the column with this name in the header, if any. See
+    /// [CsvReader::read_with_headers].
+    pub fn get(&self, headers: &HeaderIndex, name: &str) -> Option<&FieldValue> {
+        self.fields.get(headers.index_of(name)?)?.as_ref()
+    }
+}
+
+/// A row parsed like a [DefaultSchema], that keeps the error of each field that couldn't
+/// be parsed, to know exactly which fields are invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckedSchema {
+    fields: Vec<Result<Option<FieldValue>, ParseError>>,
+}
+
+impl CheckedSchema {
+    pub fn new(fields: Vec<Result<Option<FieldValue>, ParseError>>) -> Self {
+        Self { fields }
+    }
+
+    pub fn fields(&self) -> &[Result<Option<FieldValue>, ParseError>] {
+        &self.fields
+    }
+
+    /// Returns the 0-based index of the columns whose field couldn't be parsed.
+    pub fn invalid_columns(&self) -> Vec<usize> {
+        self.fields
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.is_err())
+            .map(|(i, _)| i)
+            .collect()
+    }
+}
+
+/// Converts the rows into a table of strings, where each [FieldValue] is stringified
+/// and empty fields become empty strings. Rows shorter than the widest row are padded
+/// with empty strings, so that every row of the table has the same length.
+pub fn to_string_table(rows: &[DefaultSchema]) -> Vec<Vec<String>> {
+    let width = rows.iter().map(|r| r.fields.len()).max().unwrap_or(0);
+
+    rows.iter()
+        .map(|row| {
+            let mut cells: Vec<String> = row
+                .fields
+                .iter()
+                .map(|f| f.as_ref().map(|v| v.to_string()).unwrap_or_default())
+                .collect();
+            cells.resize(width, String::new());
+            cells
+        })
+        .collect()
+}
+
+/// Serializes the rows as a JSON array. With headers, each row is an object keyed by
+/// column name, otherwise an array of values. Empty fields are `null`, as well as floats
+/// that JSON cannot represent, such as `NaN`. The fields of a row beyond the headers are
+/// keyed by their 0-based column index.
+#[cfg(feature = "serde")]
+pub fn to_json(rows: &[DefaultSchema], headers: Option<&[String]>) -> String {
+    use serde_json::{json, Map, Value};
+
+    let value = |field: &Option<FieldValue>| match field {
+        None => Value::Null,
+        Some(FieldValue::Float(v)) => json!(v),
+        Some(FieldValue::String(v)) => json!(v),
+        Some(FieldValue::Int(v)) => json!(v),
+        Some(FieldValue::Bool(v)) => json!(v),
+    };
+
+    let rows: Vec<Value> = rows
+        .iter()
+        .map(|row| match headers {
+            None => row.fields.iter().map(value).collect(),
+            Some(headers) => row
+                .fields
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let name = headers.get(i).cloned().unwrap_or_else(|| i.to_string());
+                    (name, value(field))
+                })
+                .collect::<Map<_, _>>()
+                .into(),
+        })
+        .collect();
+
+    Value::Array(rows).to_string()
+}
+
+/// Converts the values of the column to the type, such as to fix a column inferred as
+/// strings in some rows. Each value is stringified then parsed as this type with the
+/// default [ParseContext].
+///
+/// Returns the indices of the rows whose value cannot be converted, which are left
+/// unchanged. Empty fields and rows without this column are left as is.
+pub fn coerce_column(rows: &mut [DefaultSchema], column: usize, ty: ColumnType) -> Vec<usize> {
+    let context = ParseContext::default();
+    let mut failed = Vec::new();
+
+    for (index, row) in rows.iter_mut().enumerate() {
+        let Some(Some(value)) = row.fields.get_mut(column) else {
+            continue;
+        };
+        if value.column_type() == ty {
+            continue;
+        }
+        match ty.parse(value.to_string().as_bytes(), &context) {
+            Ok(coerced) => *value = coerced,
+            Err(_) => failed.push(index),
+        }
+    }
+
+    failed
+}
+
+/// The values of each column of a document, keyed by column name.
+/// See [CsvReader::read_columns_by_name].
+pub type Columns = HashMap<String, Vec<Option<FieldValue>>>;
+
+/// The original order of the columns and the rows of a document read by name.
+/// See [CsvReader::read_by_name].
+pub type MappedRows<Schema> = (ColumnOrder, Vec<MappedRow<Schema>>);
+
+/// A document transcoded to UTF-8 and the context to parse it with.
+/// See [CsvReader::document].
+type Document<'a, 's> = (Cow<'a, [u8]>, Cow<'s, ParseContext>);
+
+/// The header names and the rows of a section of a document.
+/// See [CsvReader::read_sections].
+pub type Section<Schema> = (Vec<String>, Vec<Schema>);
+
+/// Returns the document in UTF-8: with the `encoding` feature, a UTF-16 document is
+/// transcoded, see [encoding::decode]. Otherwise, the document is borrowed as is.
+fn decode(span: &[u8]) -> Cow<'_, [u8]> {
+    #[cfg(feature = "encoding")]
+    return encoding::decode(span);
+
+    #[cfg(not(feature = "encoding"))]
+    Cow::Borrowed(span)
+}
+
+/// Iterates over the rows of the iterator after the header rows, with their 1-based line
+/// number. The iterator can be borrowed, such as to collect its comments once the rows
+/// are read.
+fn data_rows<'a>(
+    mut iterator: impl BorrowMut<RowIterator<'a>>,
+    header_rows: usize,
+) -> impl Iterator<Item = (usize, &'a RowSpan)> {
+    std::iter::from_fn(move || {
+        let iterator = iterator.borrow_mut();
+        let row = iterator.next()?;
+        Some((iterator.line(), row))
+    })
+    .skip(header_rows)
 }
 
 pub struct CsvReader<Schema = DefaultSchema> {
     schema: PhantomData<Schema>,
+    context: ParseContext,
 }
 
-impl<Schema: IntoRowParser<Schema>> Default for CsvReader<Schema> {
+impl<Schema> Default for CsvReader<Schema> {
     fn default() -> Self {
         Self {
             schema: PhantomData,
+            context: ParseContext::default(),
         }
     }
 }
 
-struct RowIterator<'a> {
+/// Iterates over the rows of a document, such as to read it without parsing its fields.
+/// The records are split on the record terminator of the context, unless another
+/// [RecordSplitter] is used.
+pub struct RowIterator<'a, S: RecordSplitter = TerminatorSplitter> {
+    context: Cow<'a, ParseContext>,
     data: &'a [u8],
+    splitter: S,
     offset: usize,
+    row_start: usize,
+    line: usize,
+    trim_cr: bool,
+    comments: Vec<&'a RowSpan>,
 }
 
 impl<'a> RowIterator<'a> {
-    pub fn new(data: &'a [u8]) -> Self {
-        Self { data, offset: 0 }
+    /// Iterates over the rows of the document. A leading `sep=` line and comment lines are
+    /// skipped, but still counted by [RowIterator::line], see [ParseContext::for_document].
+    /// A newline inside a quoted field is part of the row.
+    ///
+    /// The trailing `\r` of the rows is only removed if the record terminator is `\n`.
+    pub fn new(data: &'a [u8], context: &'a ParseContext) -> Self {
+        let terminator = context.detect_record_terminator(data);
+        let splitter = TerminatorSplitter::quoted(terminator, &context.for_document(data));
+        let mut iterator = Self::with_splitter(data, context, splitter);
+        iterator.trim_cr &= terminator == NEWLINE;
+        iterator
+    }
+}
+
+impl<'a, S: RecordSplitter> RowIterator<'a, S> {
+    /// Iterates over the records of the document found by the splitter, like
+    /// [RowIterator::new].
+    pub fn with_splitter(data: &'a [u8], context: &'a ParseContext, splitter: S) -> Self {
+        let mut iterator = Self {
+            context: context.for_document(data),
+            data,
+            splitter,
+            offset: 0,
+            row_start: 0,
+            line: 0,
+            trim_cr: context.trim_cr(),
+            comments: Vec::new(),
+        };
+
+        if parser::context::sep_line(data).is_some() {
+            iterator.next_line();
+        }
+
+        iterator
+    }
+
+    /// Returns the next line, whether it is a row or a comment.
+    fn next_line(&mut self) -> Option<&'a RowSpan> {
+        let (record, next) = self.splitter.next_record(self.data, self.offset)?;
+        self.row_start = self.offset;
+        self.offset = next;
+        self.line += 1;
+
+        Some(self.trim(record))
+    }
+
+    /// The comment lines skipped so far, without their comment character, if comments
+    /// are collected, see [parser::CommentMode].
+    pub fn comments(&self) -> &[&'a RowSpan] {
+        &self.comments
+    }
+
+    /// Removes the trailing `\r` of the row, if enabled.
+    fn trim(&self, row: &'a RowSpan) -> &'a RowSpan {
+        match row.last() {
+            Some(b'\r') if self.trim_cr => &row[..row.len() - 1],
+            _ => row,
+        }
+    }
+
+    /// The context to parse the rows with, see [ParseContext::for_document].
+    pub fn context(&self) -> &ParseContext {
+        &self.context
+    }
+
+    /// The position in the document of the last row returned by the iterator.
+    pub fn row_start(&self) -> usize {
+        self.row_start
+    }
+
+    /// The 1-based line number of the last row returned by the iterator.
+    pub fn line(&self) -> usize {
+        self.line
     }
 }
 
-impl<'a> Iterator for RowIterator<'a> {
+impl<'a, S: RecordSplitter> Iterator for RowIterator<'a, S> {
     type Item = &'a RowSpan;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.offset >= self.data.len() {
-            return None;
+        loop {
+            let line = self.next_line()?;
+
+            if self.context.is_skipped_row(line) {
+                continue;
+            }
+
+            match self.context.comment_text(line) {
+                None => return Some(line),
+                Some(text) => {
+                    if self.context.comment_mode() == CommentMode::Collect {
+                        self.comments.push(text);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl CsvReader<DefaultSchema> {
+    /// Creates a reader of rows of any number of columns, whose types are inferred, see
+    /// [DefaultSchema].
+    ///
+    /// ```
+    /// use rust_csv_reader::{parser::FieldValue, CsvReader};
+    ///
+    /// let path = std::env::temp_dir().join(format!("default-schema-{}.csv", std::process::id()));
+    /// std::fs::write(&path, "name,height\nfoo,1.5\n").unwrap();
+    ///
+    /// let rows = CsvReader::with_default_schema().read_file(&path).unwrap();
+    /// std::fs::remove_file(&path).unwrap();
+    ///
+    /// assert_eq!(Some(FieldValue::Float(1.5)), rows[0].fields()[1]);
+    /// ```
+    pub fn with_default_schema() -> Self {
+        Self::default()
+    }
+}
+
+impl<Schema> CsvReader<Schema> {
+    /// Uses the settings of the context to parse documents.
+    pub fn with_context(mut self, context: ParseContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Sets whether the first row of documents is a header, enabled by default. Without
+    /// a header, every row is a data row. This is [ParseContextBuilder::header_rows] set
+    /// to 0, or to 1 if it was 0.
+    ///
+    /// [ParseContextBuilder::header_rows]: parser::ParseContextBuilder::header_rows
+    pub fn has_headers(mut self, has_headers: bool) -> Self {
+        self.context.header_rows = if has_headers {
+            self.context.header_rows.max(1)
+        } else {
+            0
+        };
+        self
+    }
+
+    /// Uses this delimiter to parse documents, keeping the other settings of the context.
+    ///
+    /// Returns an error if the delimiter conflicts with the other settings, or if it is a
+    /// newline or a carriage return.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Result<Self, ConfigError> {
+        self.context.delimiter = delimiter;
+        self.context.validate()?;
+        Ok(self)
+    }
+
+    /// Ends each row with this character instead of `\n`, such as the ASCII record
+    /// separator `0x1E`, keeping the other settings of the context.
+    ///
+    /// Returns an error if the terminator is the delimiter.
+    pub fn with_terminator(mut self, terminator: u8) -> Result<Self, ConfigError> {
+        self.context.record_terminator = Some(terminator);
+        self.context.validate()?;
+        Ok(self)
+    }
+
+    /// Expects `count` fields in each row, keeping the other settings of the context,
+    /// see [ParseContextBuilder::column_count].
+    ///
+    /// Returns an error if the count is 0.
+    ///
+    /// [ParseContextBuilder::column_count]: parser::ParseContextBuilder::column_count
+    pub fn with_column_count(mut self, count: usize) -> Result<Self, ConfigError> {
+        self.context.column_count = Some(count);
+        self.context.validate()?;
+        Ok(self)
+    }
+
+    /// Skips the lines whose first non-whitespace character is the comment character,
+    /// such as `#`, keeping the other settings of the context.
+    ///
+    /// Returns an error if the comment character is the delimiter.
+    pub fn with_comment(mut self, comment: u8) -> Result<Self, ConfigError> {
+        self.context.comment = Some(comment);
+        self.context.validate()?;
+        Ok(self)
+    }
+
+    pub fn context(&self) -> &ParseContext {
+        &self.context
+    }
+
+    /// Returns the document to parse and the context to parse it with. Every method of the
+    /// reader goes through it: the document is transcoded to UTF-8 if needed, see
+    /// [decode], then the settings it declares are applied, see [ParseContext::for_document].
+    ///
+    /// Returns an error if the delimiter declared by a `sep=` line is invalid.
+    fn document<'a, 's>(&'s self, span: &'a [u8]) -> Result<Document<'a, 's>, ConfigError> {
+        let data = decode(span);
+        let context = self.context.try_for_document(&data)?;
+        Ok((data, context))
+    }
+
+    /// Like [CsvReader::document], for the methods that cannot return an error: an invalid
+    /// delimiter declared by a `sep=` line is ignored.
+    fn lenient_document<'a, 's>(&'s self, span: &'a [u8]) -> Document<'a, 's> {
+        let data = decode(span);
+        let context = self.context.for_document(&data);
+        (data, context)
+    }
+
+    /// Returns the header of the document, or an empty row if it has no header. A header of
+    /// several rows is returned with the line breaks between them, see [header::header_names].
+    fn header<'a>(span: &'a [u8], context: &'a ParseContext) -> &'a RowSpan {
+        let mut iterator = RowIterator::new(span, context);
+        let mut range: Option<(usize, usize)> = None;
+
+        for _ in 0..context.header_rows() {
+            let Some(row) = iterator.next() else {
+                break;
+            };
+            let start = range.map_or(iterator.row_start(), |(start, _)| start);
+            range = Some((start, iterator.row_start() + row.len()));
         }
-        if let Some(index) = memchr::memchr(NEWLINE, &self.data[self.offset..]) {
-            let result = Some(&self.data[self.offset..self.offset + index]);
-            self.offset += index + 1;
-            return result;
+
+        match range {
+            Some((start, end)) => &span[start..end],
+            None => &[],
         }
+    }
 
-        None
+    /// Iterates over the data rows of the document, with their 1-based line number.
+    fn rows<'a>(
+        span: &'a [u8],
+        context: &'a ParseContext,
+    ) -> impl Iterator<Item = (usize, &'a RowSpan)> + 'a {
+        data_rows(RowIterator::new(span, context), context.header_rows())
     }
 }
 
 impl<Schema: IntoRowParser<Schema>> CsvReader<Schema> {
-    pub fn with_default_schema() -> CsvReader<DefaultSchema> {
-        CsvReader::<DefaultSchema>::default()
+    /// Parses the row found at the 1-based line number, checking it if needed.
+    fn parse_row(
+        number: usize,
+        line: &RowSpan,
+        context: &ParseContext,
+    ) -> Result<Schema, ParseError> {
+        <Schema as IntoRowParser<Schema>>::Parser::parse_checked(line, number, context)
+    }
+
+    /// Estimates the number of data rows from the average length of the first rows,
+    /// to allocate the result once instead of growing it row by row.
+    fn estimate_row_count(span: &[u8], context: &ParseContext) -> usize {
+        let mut iterator = RowIterator::new(span, context);
+        let header: usize = iterator
+            .by_ref()
+            .take(context.header_rows())
+            .map(|h| h.len() + 1)
+            .sum();
+
+        let sampled: Vec<usize> = iterator
+            .take(ROW_COUNT_SAMPLE)
+            .map(|row| row.len() + 1)
+            .collect();
+
+        if sampled.is_empty() {
+            return 0;
+        }
+
+        // The first rows may be much shorter than the others, so the estimate is capped:
+        // the result grows as usual beyond it.
+        let average = sampled.iter().sum::<usize>() / sampled.len();
+        let remaining = span.len().saturating_sub(header);
+        (remaining / average.max(1))
+            .min(remaining / 2)
+            .min(MAX_ESTIMATED_ROWS)
     }
 
+    /// Reads the rows of the document. The first row is the header.
+    ///
+    /// If the first line declares the delimiter, such as `sep=;` as written by Excel,
+    /// this delimiter is used and the line is skipped. This holds for every method of
+    /// the reader. A declared delimiter that conflicts with the other settings, such as
+    /// `sep="`, is a [ConfigError], or is ignored by the methods that cannot fail.
+    ///
+    /// With the `encoding` feature, a UTF-16 document is detected by its byte order mark
+    /// and transcoded to UTF-8 before parsing, see [encoding::decode]. This also holds for
+    /// every method of the reader.
     pub fn read(&self, span: &[u8]) -> Result<Vec<Schema>, Box<dyn Error>> {
-        let mut result: Vec<Schema> = Vec::new();
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut result: Vec<Schema> = Vec::with_capacity(Self::estimate_row_count(span, &context));
+
+        for (number, line) in Self::rows(span, &context) {
+            result.push(Self::parse_row(number, line, &context)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Reads the rows of the document lazily: each row is parsed when the iterator reaches
+    /// it, so that a large document is never entirely held in memory, such as with
+    /// `reader.read_iter(&mmap).take(100)`.
+    ///
+    /// A UTF-16 document is transcoded to a new buffer, that the iterator cannot borrow,
+    /// so its rows are all parsed at once.
+    ///
+    /// If the delimiter declared by a `sep=` line is invalid, the only item is
+    /// [ParseError::InvalidConfig].
+    pub fn read_iter<'a>(
+        &'a self,
+        span: &'a [u8],
+    ) -> impl Iterator<Item = Result<Schema, ParseError>> + 'a {
+        let rows: Box<dyn Iterator<Item = Result<Schema, ParseError>> + 'a> =
+            match self.document(span) {
+                Err(e) => Box::new(std::iter::once(Err(ParseError::InvalidConfig(e)))),
+                Ok((Cow::Borrowed(span), context)) => {
+                    Box::new(Self::iter_rows(span, &self.context, context))
+                }
+                Ok((Cow::Owned(data), context)) => Box::new(
+                    Self::iter_rows(&data, &self.context, context)
+                        .collect::<Vec<_>>()
+                        .into_iter(),
+                ),
+            };
+        rows
+    }
+
+    /// Parses the data rows of the document lazily with the context of the document, see
+    /// [CsvReader::read_iter].
+    ///
+    /// The iterator owns the context of the document, so the rows are found with the base
+    /// context, that applies the same `sep=` line, see [RowIterator::new].
+    fn iter_rows<'a>(
+        span: &'a [u8],
+        base: &'a ParseContext,
+        context: Cow<'a, ParseContext>,
+    ) -> impl Iterator<Item = Result<Schema, ParseError>> + 'a {
+        Self::rows(span, base).map(move |(number, line)| Self::parse_row(number, line, &context))
+    }
+
+    /// Reads the rows of the document like [CsvReader::read], but parses the rows in
+    /// parallel on the threads of the global rayon pool. The rows are found first, then
+    /// parsed, and returned in the order of the document.
+    ///
+    /// If several rows are invalid, the error of any of them may be returned.
+    #[cfg(feature = "rayon")]
+    pub fn read_parallel(&self, span: &[u8]) -> Result<Vec<Schema>, Box<dyn Error>>
+    where
+        Schema: Send,
+    {
+        use rayon::prelude::*;
+
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let rows: Vec<(usize, &RowSpan)> = Self::rows(span, &context).collect();
 
-        let context = ParseContext::default();
+        rows.into_par_iter()
+            .map(|(number, line)| Self::parse_row(number, line, &context))
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.into())
+    }
 
-        let iterator = RowIterator::new(span);
+    /// Reads the rows of the document like [CsvReader::read], unless the flag is set while
+    /// reading, such as by a UI thread when the user no longer needs the result.
+    ///
+    /// The flag is checked every few rows. When it is set, the rows read so far are dropped
+    /// and [ParseError::Cancelled] is returned.
+    pub fn read_cancellable(
+        &self,
+        span: &[u8],
+        cancel: &AtomicBool,
+    ) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut result: Vec<Schema> = Vec::with_capacity(Self::estimate_row_count(span, &context));
 
-        // Skip header
-        for line in iterator.skip(1) {
-            let row = <Schema as IntoRowParser<Schema>>::Parser::parse(line, &context);
-            result.push(row);
+        for (i, (number, line)) in Self::rows(span, &context).enumerate() {
+            if i % CANCELLATION_CHECK_ROWS == 0 && cancel.load(Ordering::Relaxed) {
+                return Err(ParseError::Cancelled { line: number }.into());
+            }
+            result.push(Self::parse_row(number, line, &context)?);
         }
 
         Ok(result)
     }
 
-    pub fn read_file(&self, path: &Path) -> Result<Vec<Schema>, Box<dyn Error>> {
-        let file = File::open(path)?;
+    /// Reads the rows of the document like [CsvReader::read], and the comment lines, without
+    /// their comment character, if the context collects them. See [parser::CommentMode].
+    pub fn read_with_comments(
+        &self,
+        span: &[u8],
+    ) -> Result<(Vec<Schema>, Vec<String>), Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut iterator = RowIterator::new(span, &context);
 
-        let mmap = unsafe { MmapOptions::new().map(&file).unwrap() };
+        let rows = data_rows(&mut iterator, context.header_rows())
+            .map(|(number, line)| Self::parse_row(number, line, &context))
+            .collect::<Result<_, _>>()?;
 
-        self.read(&mmap)
+        let comments = iterator
+            .comments()
+            .iter()
+            .map(|c| String::from_utf8_lossy(c).into_owned())
+            .collect();
+
+        Ok((rows, comments))
     }
-}
 
-#[macro_export]
-macro_rules! schema {
-    ($vis:vis $name:ident, $($field:ident:$type:ty),+) => {
-        paste::paste! {
-            // We generate the Row schema struct, with a field for each CSV column.
-            #[derive(Debug, PartialEq, Default, Clone)]
-            $vis struct $name {
-                $(
-                    [<$field>]: Option<[<$type>]>,
-                )+
+    /// Reads the rows of the document, each with the 1-based line number it comes from
+    /// in the source, to correlate them with the original file.
+    pub fn read_numbered(&self, span: &[u8]) -> Result<Vec<(usize, Schema)>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut result = Vec::new();
+
+        for (number, line) in Self::rows(span, &context) {
+            result.push((number, Self::parse_row(number, line, &context)?));
+        }
+
+        Ok(result)
+    }
+
+    /// Reads the fields of every data row as trimmed strings, without any type inference.
+    /// The fields are trimmed of the trim characters of the context if any, otherwise of
+    /// whitespace. Invalid UTF-8 is replaced, see [String::from_utf8_lossy].
+    pub fn read_raw_strings(&self, span: &[u8]) -> Vec<Vec<String>> {
+        let (data, context) = self.lenient_document(span);
+        let span = &*data;
+
+        Self::rows(span, &context)
+            .map(|(_, line)| {
+                RowSpanIterator::new(&context, line)
+                    .map(|field| match context.trim_chars() {
+                        Some(_) => String::from_utf8_lossy(context.trim_field(&field)).into_owned(),
+                        None => context
+                            .trim_whitespace(&String::from_utf8_lossy(&field))
+                            .to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Reads the fields of every data row as strings borrowed from the document, without
+    /// copying them: the document is validated once as UTF-8, and only the fields that had
+    /// to be unescaped, such as quoted fields with `""`, are allocated.
+    ///
+    /// The rows borrow the document, so they cannot outlive it, such as the memory map of
+    /// [CsvReader::read_file]: use [Cow::into_owned] to keep a field longer. To read the
+    /// rows into a [schema] of borrowed fields, see [CsvReader::read_borrowed].
+    ///
+    /// A UTF-16 document is transcoded to a new buffer, so its fields are all owned.
+    ///
+    /// Returns an error if the document is not valid UTF-8.
+    pub fn read_str_fields<'a>(
+        &self,
+        span: &'a [u8],
+    ) -> Result<Vec<Vec<Cow<'a, str>>>, Box<dyn Error>> {
+        match decode(span) {
+            Cow::Borrowed(span) => Self::str_fields(span, &self.context),
+            Cow::Owned(data) => Ok(Self::str_fields(&data, &self.context)?
+                .into_iter()
+                .map(|row| {
+                    row.into_iter()
+                        .map(|f| Cow::Owned(f.into_owned()))
+                        .collect()
+                })
+                .collect()),
+        }
+    }
+
+    /// Reads the fields as strings borrowed from the document, see
+    /// [CsvReader::read_str_fields].
+    fn str_fields<'a>(
+        span: &'a [u8],
+        context: &ParseContext,
+    ) -> Result<Vec<Vec<Cow<'a, str>>>, Box<dyn Error>> {
+        // The fields are sliced from the document, whose lifetime outlives the context.
+        let text = std::str::from_utf8(span)?;
+        let str_field = |field: Cow<'_, [u8]>| -> Result<Cow<'a, str>, Box<dyn Error>> {
+            match field {
+                Cow::Borrowed(bytes) => {
+                    let start = bytes.as_ptr() as usize - span.as_ptr() as usize;
+                    Ok(Cow::Borrowed(&text[start..start + bytes.len()]))
+                }
+                Cow::Owned(bytes) => Ok(Cow::Owned(String::from_utf8(bytes)?)),
             }
+        };
 
-            pub struct [<$name Parser>] {}
+        let context = context.try_for_document(span)?;
 
-            impl $crate::parser::RowParser<[<$name>]> for [<$name Parser>] {
+        Self::rows(span, &context)
+            .map(|(_, line)| {
+                RowSpanIterator::new(&context, line)
+                    .map(str_field)
+                    .collect()
+            })
+            .collect()
+    }
 
-                fn parse(row_span: &$crate::parser::RowSpan, context: &$crate::parser::ParseContext) -> $name {
-                    let mut iterator = $crate::parser::RowSpanIterator::new(context, row_span);
-                    [<$name>] {
-                        $(
-                            [<$field>]: $crate::parser::try_parse(iterator.next().unwrap()),
-                        )+
+    /// Reads every data row into a tuple, each field being parsed with the
+    /// [parser::FieldParser] of its type, such as `read_tuples::<(i64, String, f64)>(span)`.
+    pub fn read_tuples<T: TupleRow>(&self, span: &[u8]) -> Vec<T::Output> {
+        let (data, context) = self.lenient_document(span);
+        let span = &*data;
+
+        Self::rows(span, &context)
+            .map(|(_, line)| T::parse_tuple(line, &context))
+            .collect()
+    }
+
+    /// Reads every data row with the closure, which receives the fields of the row as
+    /// split by the context, with quotes removed, and returns a value of any type.
+    pub fn read_map<T>(&self, span: &[u8], f: impl Fn(&[&[u8]]) -> T) -> Vec<T> {
+        let (data, context) = self.lenient_document(span);
+        let span = &*data;
+
+        Self::rows(span, &context)
+            .map(|(_, line)| {
+                let fields: Vec<Cow<[u8]>> = RowSpanIterator::new(&context, line).collect();
+                let slices: Vec<&[u8]> = fields.iter().map(|field| &**field).collect();
+                f(&slices)
+            })
+            .collect()
+    }
+
+    /// Reads the data rows whose 0-based index is in `start..end`, such as to inspect a
+    /// part of a large document. The other rows are skipped without being parsed, and
+    /// reading stops at `end`.
+    pub fn read_line_range(
+        &self,
+        span: &[u8],
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut result = Vec::with_capacity(end.saturating_sub(start).min(MAX_ESTIMATED_ROWS));
+
+        for (number, line) in Self::rows(span, &context).take(end).skip(start) {
+            result.push(Self::parse_row(number, line, &context)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Reads `n` rows chosen at random, every row having the same probability to be
+    /// chosen. Only the chosen rows are parsed and memory stays O(n), which makes it
+    /// suitable to preview or infer the types of huge documents.
+    ///
+    /// The rows are returned in the order of the document. The same seed always
+    /// returns the same rows.
+    pub fn sample_rows(
+        &self,
+        span: &[u8],
+        n: usize,
+        seed: u64,
+    ) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let sample = sample::reservoir(Self::rows(span, &context), n, seed);
+        let mut result = Vec::with_capacity(sample.len());
+
+        for (number, line) in sample {
+            result.push(Self::parse_row(number, line, &context)?);
+        }
+
+        Ok(result)
+    }
+
+    /// Reads the rows of the document in batches of `batch_size` rows, such as for bulk
+    /// inserts into a database. The last batch has the remaining rows, and may be smaller.
+    ///
+    /// Rows are only parsed when their batch is requested, unless the document is UTF-16,
+    /// see [CsvReader::read_iter].
+    ///
+    /// When a row fails to parse, the rows of the batch parsed before it are yielded as a
+    /// smaller batch, then the error, and reading goes on with the next row. If the
+    /// delimiter declared by a `sep=` line is invalid, the only item is
+    /// [ParseError::InvalidConfig].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is 0.
+    pub fn read_batches<'a>(
+        &'a self,
+        span: &'a [u8],
+        batch_size: usize,
+    ) -> impl Iterator<Item = Result<Vec<Schema>, ParseError>> + 'a {
+        assert!(batch_size != 0, "batch size must be non-zero");
+
+        let batches: Box<dyn Iterator<Item = Result<Vec<Schema>, ParseError>> + 'a> =
+            match self.document(span) {
+                Err(e) => Box::new(std::iter::once(Err(ParseError::InvalidConfig(e)))),
+                Ok((Cow::Borrowed(span), context)) => {
+                    Box::new(Self::batches(span, &self.context, context, batch_size))
+                }
+                Ok((Cow::Owned(data), context)) => Box::new(
+                    Self::batches(&data, &self.context, context, batch_size)
+                        .collect::<Vec<_>>()
+                        .into_iter(),
+                ),
+            };
+        batches
+    }
+
+    /// Parses the data rows of the document in batches, see [CsvReader::read_batches] and
+    /// [CsvReader::iter_rows].
+    fn batches<'a>(
+        span: &'a [u8],
+        base: &'a ParseContext,
+        context: Cow<'a, ParseContext>,
+        batch_size: usize,
+    ) -> impl Iterator<Item = Result<Vec<Schema>, ParseError>> + 'a {
+        let mut rows = Self::rows(span, base);
+        let mut error = None;
+
+        std::iter::from_fn(move || {
+            if let Some(e) = error.take() {
+                return Some(Err(e));
+            }
+
+            let mut batch = Vec::with_capacity(batch_size.min(MAX_ESTIMATED_ROWS));
+
+            while batch.len() < batch_size {
+                let Some((number, line)) = rows.next() else {
+                    break;
+                };
+                match Self::parse_row(number, line, &context) {
+                    Ok(row) => batch.push(row),
+                    // The rows parsed before the error are yielded first.
+                    Err(e) if !batch.is_empty() => {
+                        error = Some(e);
+                        break;
                     }
+                    Err(e) => return Some(Err(e)),
                 }
             }
 
-            impl $crate::parser::IntoRowParser<[<$name>]> for $name {
-                type Parser = [<$name Parser>];
+            if batch.is_empty() {
+                None
+            } else {
+                Some(Ok(batch))
             }
+        })
+    }
+
+    /// Counts the data rows of the document, without the header rows, by counting the
+    /// record terminators. A last row without a terminator is counted, and a leading `sep=`
+    /// line is not, as with [CsvReader::read].
+    ///
+    /// No field is parsed: this is much faster than reading the document, but newlines
+    /// inside quoted fields, blank lines and comment lines are counted as rows as well.
+    pub fn count_rows(&self, span: &[u8]) -> usize {
+        let (data, _) = self.lenient_document(span);
+        let span = &*data;
+        let terminator = self.context.detect_record_terminator(span);
+        let mut count = memchr::memchr_iter(terminator, span).count();
+        if span.last().is_some_and(|c| *c != terminator) {
+            count += 1;
+        }
+        if parser::context::sep_line(span).is_some() {
+            count -= 1;
         }
-    };
-}
 
-#[cfg(test)]
-mod test {
-    mod row_iterator {
-        use crate::RowIterator;
+        count.saturating_sub(self.context.header_rows())
+    }
 
-        #[test]
-        fn feature() {
-            let data = b"header1,header-2\nvalue-1,value2\n";
-            let iterator = RowIterator::new(data);
+    /// Counts the data rows of the file like [CsvReader::count_rows]. The file is
+    /// memory-mapped rather than read into memory.
+    pub fn count_rows_file(&self, path: &Path) -> Result<usize, Box<dyn Error>> {
+        let file = File::open(path)?;
 
-            let lines: Vec<_> = iterator.collect();
+        // An empty file cannot be mapped on some platforms.
+        if file.metadata()?.len() == 0 {
+            return Ok(0);
+        }
 
-            assert_eq!(lines[0], b"header1,header-2");
-            assert_eq!(lines[1], b"value-1,value2");
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+
+        Ok(self.count_rows(&mmap))
+    }
+
+    /// Checks that every line of the document, including the header, has `expected` fields.
+    /// Returns the 1-based numbers of the lines that don't.
+    ///
+    /// Fields are counted with the delimiters of each line, without parsing any value:
+    /// this is much faster than reading the document, but delimiters inside quoted
+    /// fields are counted as well.
+    pub fn validate_column_count(&self, span: &[u8], expected: usize) -> Result<(), Vec<usize>> {
+        let (data, context) = self.lenient_document(span);
+        let span = &*data;
+        let mut iterator = RowIterator::new(span, &context);
+        let mut invalid = Vec::new();
+
+        while let Some(row) = iterator.next() {
+            let count = memchr::memchr_iter(context.delimiter(), row).count() + 1;
+            if count != expected {
+                invalid.push(iterator.line());
+            }
+        }
+
+        if invalid.is_empty() {
+            Ok(())
+        } else {
+            Err(invalid)
         }
     }
 
-    mod csv_parser {
-        use std::path::Path;
+    /// Checks every data row of the document and reports the problems found, with their
+    /// line, instead of stopping at the first one. Rows are expected to have as many fields
+    /// as the header. This is meant for validation jobs, see [ValidationReport::is_ok].
+    pub fn validate_report(&self, span: &[u8]) -> ValidationReport {
+        let (data, context) = self.lenient_document(span);
+        report::validate(&data, &context)
+    }
 
-        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+    /// Reads the rows of the document like [CsvReader::read], with the index of the
+    /// header row, to find the columns by name when their order varies between documents.
+    pub fn read_with_headers(
+        &self,
+        span: &[u8],
+    ) -> Result<(HeaderIndex, Vec<Schema>), Box<dyn Error>> {
+        let (data, _) = self.document(span)?;
+        Ok((self.header_index(&data), self.read(&data)?))
+    }
 
-        #[test]
-        fn read_file_1_row() {
-            let result =
-                CsvReader::<DefaultSchema>::default().read_file(Path::new("data/1-row.csv"));
+    /// Builds the index of the header row, to reuse it with [CsvReader::read_with_index].
+    pub fn header_index(&self, span: &[u8]) -> HeaderIndex {
+        let (data, context) = self.lenient_document(span);
+        let span = &*data;
+        HeaderIndex::new(Self::header(span, &context), &context)
+    }
 
-            assert!(result.is_ok());
+    /// Reads a document whose header is the one of the index, without parsing the header
+    /// again. This is useful to read many files with the same layout.
+    ///
+    /// Returns [ParseError::UnexpectedHeader] if the header row differs from the index.
+    pub fn read_with_index(
+        &self,
+        span: &[u8],
+        index: &HeaderIndex,
+    ) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let header = Self::header(span, &context);
+        if !index.matches(header) {
+            let found = String::from_utf8_lossy(header).into_owned();
+            return Err(ParseError::UnexpectedHeader(found).into());
+        }
 
-            let rows = result.unwrap();
+        self.read(span)
+    }
 
-            assert_eq!(rows.len(), 1);
+    /// Reads the column names of the header row. The header is split like data rows,
+    /// so that a quoted name can contain the delimiter.
+    pub fn read_headers(&self, span: &[u8]) -> Vec<String> {
+        let (data, context) = self.lenient_document(span);
+        let span = &*data;
+        header::header_names(Self::header(span, &context), &context)
+    }
 
-            assert_eq!(rows[0].fields[0], Some(FieldValue::String("hello".into())));
+    /// Reads the file, which is memory-mapped rather than read into memory.
+    pub fn read_file(&self, path: &Path) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let file = File::open(path)?;
+
+        // An empty file cannot be mapped on some platforms.
+        if file.metadata()?.len() == 0 {
+            return self.read(&[]);
         }
+
+        let mmap = unsafe { MmapOptions::new().map(&file)? };
+
+        self.read(&mmap)
     }
 
-    mod schema {
-        use crate::{
-            parser::{ParseContext, RowParser},
-            CsvReader,
-        };
+    /// Reads the file like [CsvReader::read_file], but first checks that its extension is
+    /// `.csv` or `.tsv`, or `.gz` with the `flate2` feature, to not read a file of another
+    /// type such as a binary file. A `.gz` file is decompressed.
+    ///
+    /// Returns an error of kind [std::io::ErrorKind::InvalidInput] for any other extension.
+    pub fn read_file_checked(&self, path: &Path) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .unwrap_or_default();
 
-        schema!(pub MySchema, name:String, height:f64);
+        if !FILE_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(Box::new(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "unexpected file type for {}, expected one of: {}",
+                    path.display(),
+                    FILE_EXTENSIONS.join(", ")
+                ),
+            )));
+        }
 
-        #[test]
-        fn parse_file() {
-            let csv = b"header1,header2\nfoo1,0.32\nfoo2,1\n";
+        if extension == "gz" {
+            return self.read_reader(File::open(path)?);
+        }
 
-            let rows = CsvReader::<MySchema>::default().read(csv).unwrap();
-            assert_eq!(rows.len(), 2);
+        self.read_file(path)
+    }
 
-            assert_eq!(rows[0].name, Some("foo1".to_string()));
-            assert_eq!(rows[0].height, Some(0.32f64));
+    /// Reads the member of a zip archive, such as a dataset shipped as a `.zip` file
+    /// containing a single CSV file. The member is decompressed in memory.
+    ///
+    /// Returns an error of kind [std::io::ErrorKind::NotFound] if the archive has no
+    /// member with this name.
+    #[cfg(feature = "zip")]
+    pub fn read_zip_member(
+        &self,
+        path: &Path,
+        member: &str,
+    ) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let mut archive = zip::ZipArchive::new(BufReader::new(File::open(path)?))?;
 
-            assert_eq!(rows[1].name, Some("foo2".to_string()));
+        let mut file = match archive.by_name(member) {
+            Ok(file) => file,
+            Err(zip::result::ZipError::FileNotFound) => {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("no member '{}' in the archive {}", member, path.display()),
+                )))
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // The size in the archive is not trusted, it may be crafted to exhaust the memory.
+        let mut data = Vec::with_capacity((file.size() as usize).min(ZIP_INITIAL_CAPACITY));
+        file.read_to_end(&mut data)?;
+        self.read(&data)
+    }
+
+    /// Reads a document from any source, such as stdin or a network stream, by buffering
+    /// it entirely in memory.
+    ///
+    /// With the `flate2` feature, a gzip stream is detected by its first bytes and
+    /// decompressed transparently.
+    pub fn read_reader<R: Read>(&self, reader: R) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let mut reader = BufReader::new(reader);
+        let mut data = Vec::new();
+
+        #[cfg(feature = "flate2")]
+        if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+            flate2::bufread::MultiGzDecoder::new(reader).read_to_end(&mut data)?;
+            return self.read(&data);
+        }
+
+        reader.read_to_end(&mut data)?;
+        self.read(&data)
+    }
+
+    /// Reads a document made of several sections separated by marker lines, such as
+    /// concatenated CSV documents. Each section is read independently, with its own
+    /// header, and is returned with its header names. The lines before the first marker
+    /// are a section too, and empty sections are skipped. The lines of the errors are
+    /// counted from the start of the document.
+    pub fn read_sections(
+        &self,
+        span: &[u8],
+        section_marker: &[u8],
+    ) -> Result<Vec<Section<Schema>>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut iterator = RowIterator::new(span, &context);
+        let mut sections = Vec::new();
+        let mut start = None;
+
+        // Each section is kept with the number of lines before it.
+        while let Some(line) = iterator.next() {
+            if line == section_marker {
+                sections.extend(
+                    start
+                        .take()
+                        .map(|(s, skipped)| (&span[s..iterator.row_start()], skipped)),
+                );
+            } else if start.is_none() {
+                start = Some((iterator.row_start(), iterator.line() - 1));
+            }
+        }
+        sections.extend(start.map(|(s, skipped)| (&span[s..], skipped)));
+
+        sections
+            .into_iter()
+            .map(|(section, skipped)| {
+                // The sections are parsed with the context of the whole document.
+                let names = header::header_names(Self::header(section, &context), &context);
+                let rows = Self::rows(section, &context)
+                    .map(|(number, line)| Self::parse_row(skipped + number, line, &context))
+                    .collect::<Result<_, _>>()?;
+                Ok((names, rows))
+            })
+            .collect()
+    }
+
+    /// Reads the rows of the document like [CsvReader::read], but keeps only one row per
+    /// value of the key in the column `key_col`, such as the latest record of each ID with
+    /// [Keep::Last]. The kept row takes the place of the first row with its key.
+    ///
+    /// The rows whose key is empty or cannot be parsed are all kept.
+    pub fn read_dedup_by_key<K: IntoFieldParser<K> + Eq + Hash>(
+        &self,
+        span: &[u8],
+        key_col: usize,
+        keep: Keep,
+    ) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut result: Vec<Schema> = Vec::new();
+        let mut positions: HashMap<K, usize> = HashMap::new();
+
+        for (number, line) in Self::rows(span, &context) {
+            let row = Self::parse_row(number, line, &context)?;
+            let key = RowSpanIterator::new(&context, line)
+                .nth(key_col)
+                .filter(|field| !context.is_empty_field(field))
+                .and_then(|field| K::Parser::parse_with_context(&field, &context).ok());
+
+            match key.map(|k| positions.get(&k).copied().ok_or(k)) {
+                Some(Ok(position)) => {
+                    if keep == Keep::Last {
+                        result[position] = row;
+                    }
+                }
+                Some(Err(key)) => {
+                    positions.insert(key, result.len());
+                    result.push(row);
+                }
+                None => result.push(row),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Reads a document from an owned buffer, such as the body of an HTTP request.
+    /// The rows own their values, so they outlive the buffer and the reader.
+    pub fn read_owned(self, data: Vec<u8>) -> Result<Vec<Schema>, Box<dyn Error>> {
+        self.read(&data)
+    }
+}
+
+impl<'a, Schema: IntoBorrowedRowParser<'a, Schema>> CsvReader<Schema> {
+    /// Reads the rows of the document like [CsvReader::read], into a [schema] declared
+    /// with a lifetime, whose `&'a str` fields are borrowed from the document instead of
+    /// being copied:
+    ///
+    /// ```
+    /// use rust_csv_reader::CsvReader;
+    ///
+    /// rust_csv_reader::schema!(pub Person<'a>, name:&'a str, age:u32);
+    ///
+    /// let data = b"name,age\nfoo,42\n".to_vec();
+    /// let rows = CsvReader::<Person>::default().read_borrowed(&data).unwrap();
+    ///
+    /// assert_eq!(Some("foo"), rows[0].name);
+    /// ```
+    ///
+    /// The rows borrow the document, so they cannot outlive it, such as the memory map of
+    /// [CsvReader::read_file]. A field that had to be unescaped, such as `"x""y"`, is not
+    /// part of the document: it is `None` in a `&str` field, and allocated in a
+    /// `Cow<str>` field, see [parser::StrParser].
+    ///
+    /// Returns [ParseError::TranscodedDocument] if the document is UTF-16, since its rows
+    /// would borrow a transcoded copy: use [CsvReader::read] instead.
+    pub fn read_borrowed(&self, span: &'a [u8]) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let Cow::Borrowed(span) = decode(span) else {
+            return Err(ParseError::TranscodedDocument.into());
+        };
+        let context = self.context.try_for_document(span)?;
+
+        let mut result = Vec::new();
+        for (number, line) in Self::rows(span, &context) {
+            // The rows are sliced from the document, whose lifetime outlives the context.
+            let row = parser::borrowed::reborrow(span, line);
+            result.push(
+                <Schema as IntoBorrowedRowParser<'a, Schema>>::Parser::parse_borrowed_checked(
+                    row, number, &context,
+                )?,
+            );
+        }
+
+        Ok(result)
+    }
+}
+
+impl<Schema: IntoRowParser<Schema> + PartialEq> CsvReader<Schema> {
+    /// Reads the rows of the document like [CsvReader::read], but drops each row that is
+    /// equal to the previous row kept, such as the runs of duplicates of sorted data.
+    /// Duplicates that are not consecutive are kept.
+    pub fn read_dedup_consecutive(&self, span: &[u8]) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut result: Vec<Schema> = Vec::new();
+
+        for (number, line) in Self::rows(span, &context) {
+            let row = Self::parse_row(number, line, &context)?;
+            if result.last() != Some(&row) {
+                result.push(row);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+impl<Schema: IntoRowParser<Schema>> CsvReader<Schema>
+where
+    Schema::Parser: StrictRowParser<Schema>,
+{
+    /// Reads the rows of the document like [CsvReader::read], but returns
+    /// [ParseError::InvalidField] for the first non-empty field that cannot be parsed,
+    /// instead of turning it into an empty field, so that corrupted values are not
+    /// mistaken for empty ones.
+    pub fn read_strict(&self, span: &[u8]) -> Result<Vec<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+
+        Self::rows(span, &context)
+            .map(|(number, line)| {
+                Schema::Parser::parse_strict_checked(line, number, &context)
+                    .map_err(|e| e.at_line(number))
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.into())
+    }
+}
+
+impl<Schema: IntoRowParser<Schema> + ExpectedHeaders> CsvReader<Schema>
+where
+    Schema::Parser: MappedRowParser<Schema>,
+{
+    /// Reads the rows, where each field of the schema is read from the column with the
+    /// same name, whatever the order of the columns. A field without a column is `None`.
+    ///
+    /// Also returns the original order of the columns, and keeps the raw values of the
+    /// columns that are not fields of the schema, to write the rows back unchanged in this
+    /// order with [CsvWriter::write_mapped].
+    ///
+    /// With [ParseContextBuilder::strict_column_count], a row with more fields than the
+    /// header is an error.
+    ///
+    /// [ParseContextBuilder::strict_column_count]: parser::ParseContextBuilder::strict_column_count
+    pub fn read_by_name(&self, span: &[u8]) -> Result<MappedRows<Schema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let names = header::header_names(Self::header(span, &context), &context);
+        let width = names.len();
+        let order = ColumnOrder::new(names, <Schema as ExpectedHeaders>::expected_headers());
+
+        let rows = Self::rows(span, &context)
+            .map(|(number, line)| {
+                // The columns that are not fields of the schema are kept, so the row is
+                // checked against the header.
+                parser::check_fields(RowSpanIterator::new(&context, line), Some(width), number)?;
+                Ok(MappedRow {
+                    row: Schema::Parser::parse_mapped(line, order.positions(), &context),
+                    unmapped: order.unmapped_fields(line, &context),
+                })
+            })
+            .collect::<Result<_, ParseError>>()?;
+
+        Ok((order, rows))
+    }
+}
+
+impl<Schema: IntoRowParser<Schema> + ExpectedHeaders> CsvReader<Schema> {
+    /// Checks that the header of the document matches the field names of the schema.
+    pub fn verify_header(
+        &self,
+        span: &[u8],
+        comparison: HeaderComparison,
+    ) -> Result<(), HeaderMismatch> {
+        let names = self.read_headers(span);
+
+        comparison.compare(&names, <Schema as ExpectedHeaders>::expected_headers())
+    }
+}
+
+impl CsvReader<DefaultSchema> {
+    /// Reads the document into columns, keyed by the names of the header row. Each column
+    /// has a value for every row, `None` if the field is empty or missing.
+    ///
+    /// As with [HeaderIndex], the first of several columns with the same name wins, and
+    /// the others are ignored.
+    pub fn read_columns_by_name(&self, span: &[u8]) -> Result<Columns, Box<dyn Error>> {
+        let index = self.header_index(span);
+        let rows = self.read(span)?;
+
+        let columns = index
+            .names()
+            .iter()
+            .filter_map(|name| {
+                let i = index.index_of(name)?;
+                let values = rows.iter().map(|row| row.fields.get(i).cloned().flatten());
+                Some((name.clone(), values.collect()))
+            })
+            .collect();
+
+        Ok(columns)
+    }
+
+    /// Reads the rows of the document into arrays of `N` fields, see
+    /// [DefaultRowParser::parse_array]. Returns [ParseError::WrongColumnCount] for the
+    /// first row with more than `N` fields.
+    pub fn read_arrays<const N: usize>(
+        &self,
+        span: &[u8],
+    ) -> Result<Vec<[Option<FieldValue>; N]>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+
+        Self::rows(span, &context)
+            .map(|(number, line)| {
+                let mut iterator = RowSpanIterator::new(&context, line);
+                let fields = DefaultRowParser::parse_array_fields(&mut iterator, &context)
+                    .map_err(|e| e.at_line(number))?;
+                parser::check_fields(iterator, Some(N), number)?;
+                Ok(fields)
+            })
+            .collect::<Result<_, ParseError>>()
+            .map_err(|e| e.into())
+    }
+
+    /// Computes statistics about each column in a single pass, without keeping the rows,
+    /// such as to profile a huge document. See [ColumnProfile].
+    pub fn profile(&self, span: &[u8]) -> Result<Vec<ColumnProfile>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let span = &*data;
+        let mut profiler = profile::Profiler::default();
+
+        for (number, line) in Self::rows(span, &context) {
+            profiler.add_row(Self::parse_row(number, line, &context)?.fields());
+        }
+
+        Ok(profiler.finish())
+    }
+
+    /// Reads the columns of the spec, in the order of the spec, each parsed as its
+    /// [parser::ColumnType]. The columns are found by their name in the header.
+    ///
+    /// Returns every problem found if the document doesn't match the spec, such as a
+    /// missing required column or a value of the wrong type.
+    pub fn read_with_spec(
+        &self,
+        span: &[u8],
+        spec: &Spec,
+    ) -> Result<Vec<DefaultSchema>, Vec<SpecError>> {
+        let (data, context) = self.lenient_document(span);
+        let (span, context) = (&*data, &*context);
+
+        let names = header::header_names(Self::header(span, context), context);
+        let positions = spec.resolve(&names)?;
+
+        let mut errors = Vec::new();
+        let rows = Self::rows(span, context)
+            .map(|(line, row)| spec.parse_row(&positions, line, row, context, &mut errors))
+            .collect();
+
+        if errors.is_empty() {
+            Ok(rows)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Reads the rows whose value in the column named `column` satisfies the predicate.
+    ///
+    /// The column is resolved by an exact match on the header row. Rows where this column
+    /// is empty are skipped. Returns [ParseError::UnknownColumn] if no column has this name.
+    pub fn read_where(
+        &self,
+        span: &[u8],
+        column: &str,
+        predicate: impl Fn(&FieldValue) -> bool,
+    ) -> Result<Vec<DefaultSchema>, Box<dyn Error>> {
+        let (data, context) = self.document(span)?;
+        let (span, context) = (&*data, &*context);
+
+        let index = header::header_names(Self::header(span, context), context)
+            .iter()
+            .position(|name| name == column)
+            .ok_or_else(|| ParseError::UnknownColumn(column.to_string()))?;
+
+        let mut result = Vec::new();
+        for (number, line) in Self::rows(span, context) {
+            let row = Self::parse_row(number, line, context)?;
+            if matches!(row.fields.get(index), Some(Some(v)) if predicate(v)) {
+                result.push(row);
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Generates a row schema struct, with a field for each CSV column, and its parser.
+///
+/// Each field is parsed with the [parser::FieldParser] of its type, unless a
+/// [parser::ConfiguredFieldParser] is given after `=`:
+///
+/// ```
+/// use rust_csv_reader::parser::ListParser;
+///
+/// rust_csv_reader::schema!(pub Post, title:String, tags:Vec<String> = ListParser::<String>::new(b'|'));
+/// ```
+///
+/// Note that the parser expression is evaluated for every row. A row with fewer fields
+/// than the schema leaves the missing fields empty. Unquoted empty fields are `None`,
+/// while quoted empty fields are parsed, see [parser::ParseContextBuilder::empty_as_null].
+///
+/// A schema declared with a lifetime can have fields borrowed from the document, such as
+/// `&'a str`, parsed with the [parser::IntoBorrowedFieldParser] of their type. Its rows
+/// are read with [CsvReader::read_borrowed]:
+///
+/// ```
+/// rust_csv_reader::schema!(pub Person<'a>, name:&'a str, city:std::borrow::Cow<'a, str>);
+/// ```
+#[macro_export]
+macro_rules! schema {
+    (@parse $span:expr, $context:expr) => {
+        $crate::parser::try_parse_with_context($span, $context)
+    };
+    (@parse $span:expr, $context:expr, $parser:expr) => {
+        $crate::parser::try_parse_with_parser(&$parser, $span, $context)
+    };
+    (@parse_borrowed $span:expr, $context:expr) => {
+        $crate::parser::try_parse_borrowed($span, $context)
+    };
+    (@parse_borrowed $span:expr, $context:expr, $parser:expr) => {
+        $crate::parser::try_parse_with_parser(&$parser, &$span, $context)
+    };
+    (@parse_column $span:expr, $column:expr, $context:expr) => {
+        $crate::parser::parse_column($span, $column, $context)
+    };
+    (@parse_column $span:expr, $column:expr, $context:expr, $parser:expr) => {
+        $crate::parser::parse_column_with_parser(&$parser, $span, $column, $context)
+    };
+    ($vis:vis $name:ident, $($field:ident:$type:ty $(= $parser:expr)?),+) => {
+        paste::paste! {
+            // We generate the Row schema struct, with a field for each CSV column.
+            #[derive(Debug, PartialEq, Default, Clone)]
+            $vis struct $name {
+                $(
+                    $field: Option<$type>,
+                )+
+            }
+
+            pub struct [<$name Parser>] {}
+
+            impl [<$name Parser>] {
+                fn parse_fields(iterator: &mut $crate::parser::RowSpanIterator, context: &$crate::parser::ParseContext) -> $name {
+                    [<$name>] {
+                        $(
+                            $field: {
+                                let span = iterator.next().unwrap_or_default();
+                                if $crate::parser::is_null(&span, iterator.is_quoted(), context) {
+                                    None
+                                } else {
+                                    $crate::schema!(@parse &span, context $(, $parser)?)
+                                }
+                            },
+                        )+
+                    }
+                }
+
+                fn parse_strict_fields(iterator: &mut $crate::parser::RowSpanIterator, context: &$crate::parser::ParseContext) -> Result<$name, $crate::parser::ParseError> {
+                    let mut columns = 0usize..;
+                    Ok([<$name>] {
+                        $(
+                            $field: {
+                                let column = columns.next().unwrap_or_default();
+                                let span = iterator.next().unwrap_or_default();
+                                if $crate::parser::is_null(&span, iterator.is_quoted(), context) {
+                                    None
+                                } else {
+                                    Some($crate::schema!(@parse_column &span, column, context $(, $parser)?)?)
+                                }
+                            },
+                        )+
+                    })
+                }
+            }
+
+            impl $crate::parser::RowParser<[<$name>]> for [<$name Parser>] {
+
+                fn parse(row_span: &$crate::parser::RowSpan, context: &$crate::parser::ParseContext) -> $name {
+                    Self::parse_fields(&mut $crate::parser::RowSpanIterator::new(context, row_span), context)
+                }
+
+                fn field_count() -> Option<usize> {
+                    Some([$(stringify!($field)),+].len())
+                }
+
+                fn parse_checked(row_span: &$crate::parser::RowSpan, line: usize, context: &$crate::parser::ParseContext) -> Result<$name, $crate::parser::ParseError> {
+                    let mut iterator = $crate::parser::RowSpanIterator::new(context, row_span);
+                    let row = Self::parse_fields(&mut iterator, context);
+                    $crate::parser::check_fields(iterator, Self::field_count(), line)?;
+                    Ok(row)
+                }
+            }
+
+            impl $crate::parser::StrictRowParser<[<$name>]> for [<$name Parser>] {
+
+                fn parse_strict(row_span: &$crate::parser::RowSpan, context: &$crate::parser::ParseContext) -> Result<$name, $crate::parser::ParseError> {
+                    Self::parse_strict_fields(&mut $crate::parser::RowSpanIterator::new(context, row_span), context)
+                }
+
+                fn parse_strict_checked(row_span: &$crate::parser::RowSpan, line: usize, context: &$crate::parser::ParseContext) -> Result<$name, $crate::parser::ParseError> {
+                    use $crate::parser::RowParser;
+
+                    let mut iterator = $crate::parser::RowSpanIterator::new(context, row_span);
+                    let row = Self::parse_strict_fields(&mut iterator, context);
+                    $crate::parser::check_fields(iterator, Self::field_count(), line)?;
+                    row
+                }
+            }
+
+            impl $crate::parser::MappedRowParser<[<$name>]> for [<$name Parser>] {
+
+                fn parse_mapped(row_span: &$crate::parser::RowSpan, positions: &[Option<usize>], context: &$crate::parser::ParseContext) -> $name {
+                    let mut iterator = $crate::parser::RowSpanIterator::new(context, row_span);
+                    let mut fields = Vec::new();
+                    while let Some(span) = iterator.next() {
+                        fields.push((span, iterator.is_quoted()));
+                    }
+
+                    let mut positions = positions.iter();
+                    [<$name>] {
+                        $(
+                            $field: match positions.next().copied().flatten().and_then(|p| fields.get(p)) {
+                                Some((span, quoted)) if !$crate::parser::is_null(span, *quoted, context) => {
+                                    $crate::schema!(@parse span, context $(, $parser)?)
+                                }
+                                _ => None,
+                            },
+                        )+
+                    }
+                }
+            }
+
+            impl $crate::parser::IntoRowParser<[<$name>]> for $name {
+                type Parser = [<$name Parser>];
+            }
+
+            impl $crate::header::ExpectedHeaders for $name {
+                fn expected_headers() -> &'static [&'static str] {
+                    &[$(stringify!($field)),+]
+                }
+            }
+        }
+    };
+    ($vis:vis $name:ident<$lt:lifetime>, $($field:ident:$type:ty $(= $parser:expr)?),+) => {
+        paste::paste! {
+            // We generate the Row schema struct, whose fields can borrow from the document.
+            #[derive(Debug, PartialEq, Default, Clone)]
+            $vis struct $name<$lt> {
+                $(
+                    $field: Option<$type>,
+                )+
+            }
+
+            pub struct [<$name Parser>] {}
+
+            impl [<$name Parser>] {
+                fn parse_fields<$lt>(row_span: &$lt $crate::parser::RowSpan, iterator: &mut $crate::parser::RowSpanIterator, context: &$crate::parser::ParseContext) -> $name<$lt> {
+                    [<$name>] {
+                        $(
+                            $field: {
+                                let span = $crate::parser::borrow_field(row_span, iterator.next().unwrap_or_default());
+                                if $crate::parser::is_null(&span, iterator.is_quoted(), context) {
+                                    None
+                                } else {
+                                    $crate::schema!(@parse_borrowed span, context $(, $parser)?)
+                                }
+                            },
+                        )+
+                    }
+                }
+            }
+
+            impl<$lt> $crate::parser::BorrowedRowParser<$lt, $name<$lt>> for [<$name Parser>] {
+
+                fn parse_borrowed(row_span: &$lt $crate::parser::RowSpan, context: &$crate::parser::ParseContext) -> $name<$lt> {
+                    Self::parse_fields(row_span, &mut $crate::parser::RowSpanIterator::new(context, row_span), context)
+                }
+
+                fn field_count() -> Option<usize> {
+                    Some([$(stringify!($field)),+].len())
+                }
+
+                fn parse_borrowed_checked(row_span: &$lt $crate::parser::RowSpan, line: usize, context: &$crate::parser::ParseContext) -> Result<$name<$lt>, $crate::parser::ParseError> {
+                    let mut iterator = $crate::parser::RowSpanIterator::new(context, row_span);
+                    let row = Self::parse_fields(row_span, &mut iterator, context);
+                    $crate::parser::check_fields(iterator, Self::field_count(), line)?;
+                    Ok(row)
+                }
+            }
+
+            impl<$lt> $crate::parser::IntoBorrowedRowParser<$lt, $name<$lt>> for $name<$lt> {
+                type Parser = [<$name Parser>];
+            }
+
+            impl $crate::header::ExpectedHeaders for $name<'_> {
+                fn expected_headers() -> &'static [&'static str] {
+                    &[$(stringify!($field)),+]
+                }
+            }
+        }
+    };
+}
+
+/// Generates an enum that can be used as a [schema] field, where each variant is
+/// parsed from its label:
+///
+/// ```
+/// rust_csv_reader::csv_enum!(pub Status { Active = "active", Inactive = "inactive", Other(String) });
+/// ```
+///
+/// Values that don't match any label are stored in the catch-all variant, if any.
+/// Otherwise, parsing them fails with [parser::ParseError::UnknownLabel]. The labels are
+/// matched, and the catch-all values stored, without the whitespace and trim characters
+/// of the context, see [parser::ParseContextBuilder::ascii_trim].
+///
+/// Each variant has either a label or a type, and there is at most one catch-all variant:
+///
+/// ```compile_fail
+/// rust_csv_reader::csv_enum!(Two { A = "a", X(String), Y(String) });
+/// ```
+///
+/// ```compile_fail
+/// rust_csv_reader::csv_enum!(Bare { A = "a", B });
+/// ```
+#[macro_export]
+macro_rules! csv_enum {
+    (@variants $head:tt { $($decls:tt)* } [$($labels:tt)*] [$($catch_all:tt)*]) => {
+        $crate::csv_enum!(@generate $head { $($decls)* } [$($labels)*] [$($catch_all)*]);
+    };
+    (@variants $head:tt { $($decls:tt)* } [$($labels:tt)*] [$($catch_all:tt)*] $variant:ident = $label:literal $(, $($rest:tt)*)?) => {
+        $crate::csv_enum!(@variants $head { $($decls)* $variant, } [$($labels)* $variant = $label,] [$($catch_all)*] $($($rest)*)?);
+    };
+    (@variants $head:tt { $($decls:tt)* } [$($labels:tt)*] [] $variant:ident($type:ty) $(, $($rest:tt)*)?) => {
+        $crate::csv_enum!(@variants $head { $($decls)* $variant($type), } [$($labels)*] [$variant($type)] $($($rest)*)?);
+    };
+    (@variants $head:tt { $($decls:tt)* } [$($labels:tt)*] [$first:ident $($first_type:tt)*] $variant:ident($type:ty) $(, $($rest:tt)*)?) => {
+        compile_error!(concat!(
+            "csv_enum! accepts a single catch-all variant, but ",
+            stringify!($variant),
+            " follows ",
+            stringify!($first)
+        ));
+    };
+    (@variants $head:tt { $($decls:tt)* } [$($labels:tt)*] [$($catch_all:tt)*] $variant:ident $(, $($rest:tt)*)?) => {
+        compile_error!(concat!(
+            "the variant ",
+            stringify!($variant),
+            " of csv_enum! needs a label, such as `= \"label\"`, or a type, such as `(String)`"
+        ));
+    };
+    (@generate ($vis:vis $name:ident) { $($decls:tt)* } [$($variant:ident = $label:literal,)*] [$($catch_all:ident($type:ty))?]) => {
+        paste::paste! {
+            #[derive(Debug, PartialEq, Clone)]
+            $vis enum $name {
+                $($decls)*
+            }
+
+            pub struct [<$name Parser>] {}
+
+            impl $crate::parser::FieldParser<$name> for [<$name Parser>] {
+                fn parse(span: &$crate::parser::FieldSpan) -> Result<$name, Box<dyn std::error::Error>> {
+                    Self::parse_with_context(span, &$crate::parser::ParseContext::default())
+                }
+
+                // The catch-all variant, when present, makes the final error unreachable.
+                #[allow(unreachable_code)]
+                fn parse_with_context(span: &$crate::parser::FieldSpan, context: &$crate::parser::ParseContext) -> Result<$name, Box<dyn std::error::Error>> {
+                    let label = $crate::parser::trim_label(span, context)?;
+                    $(
+                        if label == $label {
+                            return Ok($name::$variant);
+                        }
+                    )*
+                    $(
+                        return Ok($name::$catch_all(<$type>::from(label)));
+                    )?
+                    Err($crate::parser::ParseError::UnknownLabel(label.to_string()).into())
+                }
+            }
+
+            impl $crate::parser::IntoFieldParser<$name> for $name {
+                type Parser = [<$name Parser>];
+            }
+        }
+    };
+    ($vis:vis $name:ident { $($variants:tt)* }) => {
+        $crate::csv_enum!(@variants ($vis $name) {} [] [] $($variants)*);
+    };
+}
+
+#[cfg(test)]
+mod test {
+    mod row_iterator {
+        use crate::{
+            parser::{FieldValue, ParseContext},
+            CsvReader, DefaultSchema, RowIterator,
+        };
+
+        #[test]
+        fn feature() {
+            let data = b"header1,header-2\nvalue-1,value2\n";
+            let context = ParseContext::default();
+            let iterator = RowIterator::new(data, &context);
+
+            let lines: Vec<_> = iterator.collect();
+
+            assert_eq!(lines[0], b"header1,header-2");
+            assert_eq!(lines[1], b"value-1,value2");
+        }
+
+        #[test]
+        fn custom_terminator() {
+            let data = b"a,b\r\x1ec,\"d\x1e\"\x1ee,f";
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .has_headers(false)
+                .with_terminator(0x1e)
+                .unwrap()
+                .read_str_fields(data)
+                .unwrap();
+
+            assert_eq!(
+                vec![vec!["a", "b\r"], vec!["c", "d\x1e"], vec!["e", "f"]],
+                rows
+            );
+        }
+
+        #[test]
+        fn last_row_without_newline() {
+            let context = ParseContext::default();
+
+            let lines: Vec<_> = RowIterator::new(b"a,b\nc,d", &context).collect();
+
+            assert_eq!(vec![&b"a,b"[..], b"c,d"], lines);
+        }
+
+        #[test]
+        fn line() {
+            let context = ParseContext::default();
+            let mut iterator = RowIterator::new(b"a\nb\n", &context);
+
+            assert_eq!(0, iterator.line());
+            iterator.next();
+            assert_eq!(1, iterator.line());
+            iterator.next();
+            assert_eq!(2, iterator.line());
+        }
+
+        #[test]
+        fn mixed_line_endings() {
+            let data = b"a,b\r\nc,d\ne,f\r\n";
+            let context = ParseContext::default();
+
+            let lines: Vec<_> = RowIterator::new(data, &context).collect();
+
+            assert_eq!(lines, vec![&b"a,b"[..], &b"c,d"[..], &b"e,f"[..]]);
+        }
+
+        #[test]
+        fn crlf_line_endings_are_not_part_of_the_last_field() {
+            let context = ParseContext::builder().delimiter(b';').build().unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            let rows = reader.read(b"a;b\r\nc;1.5\r\nd;2\n").unwrap();
+
+            assert_eq!(Some(FieldValue::Float(1.5)), rows[0].fields()[1]);
+            assert_eq!(Some(FieldValue::Int(2)), rows[1].fields()[1]);
+            assert_eq!(vec!["a", "b"], reader.read_headers(b"a;b\r\nc;d\r\n"));
+        }
+
+        #[test]
+        fn skip_empty_rows() {
+            let data = b"a,b\n\n1,2\n  \n,,\n3,4\n";
+            let rows = |context: ParseContext| -> Vec<Vec<u8>> {
+                RowIterator::new(data, &context)
+                    .map(|r| r.to_vec())
+                    .collect()
+            };
+
+            assert_eq!(6, rows(ParseContext::default()).len());
+
+            let context = ParseContext::builder()
+                .skip_empty_rows(true)
+                .build()
+                .unwrap();
+            assert_eq!(
+                vec![b"a,b".to_vec(), b"1,2".into(), b",,".into(), b"3,4".into()],
+                rows(context)
+            );
+
+            let context = ParseContext::builder()
+                .skip_delimiter_only_rows(true)
+                .build()
+                .unwrap();
+            assert_eq!(
+                vec![b"a,b".to_vec(), b"1,2".into(), b"3,4".into()],
+                rows(context)
+            );
+        }
+
+        #[test]
+        fn skipped_rows_are_counted_as_lines() {
+            let context = ParseContext::builder()
+                .skip_empty_rows(true)
+                .build()
+                .unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            let rows = reader.read_numbered(b"a\n\n1\n\n\n2\n").unwrap();
+
+            assert_eq!(vec![3, 6], rows.iter().map(|(n, _)| *n).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn keeps_carriage_return_when_disabled() {
+            let data = b"a,b\r\nc,d\n";
+            let context = ParseContext::builder().trim_cr(false).build().unwrap();
+
+            let lines: Vec<_> = RowIterator::new(data, &context).collect();
+
+            assert_eq!(lines, vec![&b"a,b\r"[..], &b"c,d"[..]]);
+        }
+
+        #[test]
+        fn detects_carriage_return_only_terminator() {
+            let data = b"a,b\rc,d\re,f\r";
+            let context = ParseContext::default();
+
+            let lines: Vec<_> = RowIterator::new(data, &context).collect();
+
+            assert_eq!(lines, vec![&b"a,b"[..], &b"c,d"[..], &b"e,f"[..]]);
+        }
+
+        #[test]
+        fn configured_record_terminator() {
+            let data = b"a,b;c,d\n;";
+            let context = ParseContext::builder()
+                .record_terminator(Some(b';'))
+                .build()
+                .unwrap();
+
+            let lines: Vec<_> = RowIterator::new(data, &context).collect();
+
+            assert_eq!(lines, vec![&b"a,b"[..], &b"c,d\n"[..]]);
+        }
+    }
+
+    mod csv_parser {
+        use std::path::Path;
+
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        #[test]
+        fn read_file_1_row() {
+            let result =
+                CsvReader::<DefaultSchema>::default().read_file(Path::new("data/1-row.csv"));
+
+            assert!(result.is_ok());
+
+            let rows = result.unwrap();
+
+            assert_eq!(rows.len(), 1);
+
+            assert_eq!(rows[0].fields[0], Some(FieldValue::String("hello".into())));
+        }
+    }
+
+    mod header_rows {
+        use crate::{
+            parser::{FieldValue, ParseContext},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn joins_header_rows_into_composite_names() {
+            let csv = b"Region,Sales,,Costs\n,Q1,Q2,Q1\nnorth,1,2,3\n";
+            let context = ParseContext::builder().header_rows(2).build().unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            let headers = reader.read_headers(csv);
+            let rows = reader.read_numbered(csv).unwrap();
+
+            assert_eq!(
+                vec!["Region", "Sales / Q1", "Sales / Q2", "Costs / Q1"],
+                headers
+            );
+            assert_eq!(1, rows.len());
+            assert_eq!(3, rows[0].0);
+            assert_eq!(Some(FieldValue::Int(2)), rows[0].1.fields()[2]);
+            assert_eq!(Some(2), reader.header_index(csv).index_of("Sales / Q2"));
+        }
+
+        #[test]
+        fn custom_separator() {
+            let csv = b"a,b\nx,y\n";
+            let context = ParseContext::builder()
+                .header_rows(2)
+                .header_separator("_")
+                .build()
+                .unwrap();
+
+            let headers = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .read_headers(csv);
+
+            assert_eq!(vec!["a_x", "b_y"], headers);
+        }
+
+        #[test]
+        fn header_rows_of_unequal_width() {
+            let context = ParseContext::builder().header_rows(2).build().unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            assert_eq!(
+                vec!["Region", "Sales / Q1", "Sales / Q2", "Sales / Total"],
+                reader.read_headers(b"Region,Sales\n,Q1,Q2,Total\nnorth,1,2,3\n")
+            );
+            assert_eq!(
+                vec!["a / x", "b", "c"],
+                reader.read_headers(b"a,b,c\nx\n1,2,3\n")
+            );
+        }
+
+        #[test]
+        fn quoted_line_break_in_header_rows() {
+            let context = ParseContext::builder().header_rows(2).build().unwrap();
+
+            let headers = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .read_headers(b"\"Sales\nEUR\",Costs\nQ1,Q1\n1,2\n");
+
+            assert_eq!(vec!["Sales\nEUR / Q1", "Costs / Q1"], headers);
+        }
+    }
+
+    mod has_header {
+        use crate::{
+            parser::{FieldValue, ParseContext},
+            CsvReader, DefaultSchema,
+        };
+
+        fn reader() -> CsvReader<DefaultSchema> {
+            let context = ParseContext::builder().has_header(false).build().unwrap();
+            CsvReader::<DefaultSchema>::default().with_context(context)
+        }
+
+        #[test]
+        fn read_returns_every_line() {
+            let csv = b"foo,1\nbar,2\n";
+
+            let rows = reader().read_numbered(csv).unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(1, rows[0].0);
+            assert_eq!(
+                Some(FieldValue::String("foo".into())),
+                rows[0].1.fields()[0]
+            );
+            assert_eq!(Some(FieldValue::Int(2)), rows[1].1.fields()[1]);
+        }
+
+        #[test]
+        fn batches_and_sample_include_first_line() {
+            let csv = b"1\n2\n3\n";
+            let reader = reader();
+
+            assert_eq!(3, reader.read_batches(csv, 2).flatten().flatten().count());
+            assert_eq!(3, reader.sample_rows(csv, 10, 0).unwrap().len());
+            assert_eq!(3, reader.validate_report(csv).rows);
+        }
+
+        #[test]
+        fn read_headers_returns_no_names() {
+            assert!(reader().read_headers(b"foo,1\n").is_empty());
+        }
+    }
+
+    mod comments {
+        use crate::{
+            parser::{CommentMode, FieldValue, ParseContext},
+            CsvReader, DefaultSchema,
+        };
+
+        const CSV: &[u8] = b"# source: sensors\nname,value\nfoo,1\n# calibrated\nbar,2\n";
+
+        #[test]
+        fn comment_lines_are_skipped() {
+            let context = ParseContext::builder().comment(Some(b'#')).build().unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            let (rows, comments) = reader.read_with_comments(CSV).unwrap();
+
+            assert_eq!(vec!["name", "value"], reader.read_headers(CSV));
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::Int(2)), rows[1].fields()[1]);
+            assert!(comments.is_empty());
+            assert_eq!(5, reader.read_numbered(CSV).unwrap()[1].0);
+        }
+
+        #[test]
+        fn with_comment_skips_metadata_lines() {
+            let csv = b"# generated 2024\n  # by exporter\nname,value\nfoo,#1\n# note\nbar#,2\n";
+            let reader = CsvReader::<DefaultSchema>::default()
+                .with_comment(b'#')
+                .unwrap();
+
+            let rows = reader.read(csv).unwrap();
+
+            assert_eq!(vec!["name", "value"], reader.read_headers(csv));
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::String("#1".into())), rows[0].fields()[1]);
+            assert_eq!(Some(FieldValue::String("bar#".into())), rows[1].fields()[0]);
+        }
+
+        #[test]
+        fn invalid_declared_delimiter_returns_err() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            assert!(reader.read_with_comments(b"sep=\"\nn\n1\n").is_err());
+        }
+
+        #[test]
+        fn with_comment_rejects_the_delimiter() {
+            assert!(CsvReader::<DefaultSchema>::default()
+                .with_comment(b',')
+                .is_err());
+        }
+
+        #[test]
+        fn comment_lines_are_collected() {
+            let context = ParseContext::builder()
+                .comment(Some(b'#'))
+                .comment_mode(CommentMode::Collect)
+                .build()
+                .unwrap();
+
+            let (rows, comments) = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .read_with_comments(CSV)
+                .unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(vec!["source: sensors", "calibrated"], comments);
+        }
+    }
+
+    mod read_map {
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn builds_a_tuple_per_row() {
+            let csv = b"name,age\n\"Smith, John\",42\nfoo,n/a\n";
+
+            let rows = CsvReader::<DefaultSchema>::default().read_map(csv, |fields| {
+                let name = String::from_utf8_lossy(fields[0]).into_owned();
+                let age: Option<u32> = std::str::from_utf8(fields[1]).unwrap().parse().ok();
+                (name, age, fields.len())
+            });
+
+            assert_eq!(
+                vec![
+                    ("Smith, John".to_string(), Some(42), 2),
+                    ("foo".to_string(), None, 2)
+                ],
+                rows
+            );
+        }
+    }
+
+    mod read_line_range {
+        use crate::{
+            parser::{FieldValue, ParseContext},
+            CsvReader, DefaultSchema,
+        };
+
+        const CSV: &[u8] = b"n\n0\n1\n2\n3\n4\n";
+
+        fn read(start: usize, end: usize) -> Vec<Option<FieldValue>> {
+            CsvReader::<DefaultSchema>::default()
+                .read_line_range(CSV, start, end)
+                .unwrap()
+                .iter()
+                .map(|r| r.fields()[0].clone())
+                .collect()
+        }
+
+        #[test]
+        fn reads_rows_in_range() {
+            assert_eq!(
+                vec![Some(FieldValue::Int(1)), Some(FieldValue::Int(2))],
+                read(1, 3)
+            );
+        }
+
+        #[test]
+        fn range_past_the_end_is_truncated() {
+            assert_eq!(vec![Some(FieldValue::Int(4))], read(4, 10));
+            assert!(read(7, 10).is_empty());
+            assert!(read(3, 2).is_empty());
+        }
+
+        #[test]
+        fn unbounded_range_reads_remaining_rows() {
+            assert_eq!(vec![Some(FieldValue::Int(4))], read(4, usize::MAX));
+        }
+
+        #[test]
+        fn rows_outside_range_are_not_parsed() {
+            // The first row has data after a closing quote, an error with strict quotes.
+            let csv = b"n\n\"a\"b\n1\n";
+            let context = ParseContext::builder().strict_quotes(true).build().unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            assert!(reader.read(csv).is_err());
+            assert_eq!(1, reader.read_line_range(csv, 1, 2).unwrap().len());
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    mod read_parallel {
+        use crate::{
+            parser::{ParseContext, ParseError},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn returns_the_rows_of_read_in_order() {
+            let mut csv = b"id,name,score\n".to_vec();
+            for i in 0..5000 {
+                csv.extend_from_slice(format!("{},name {},{}.5\n", i, i, i % 7).as_bytes());
+            }
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            assert_eq!(
+                reader.read(&csv).unwrap(),
+                reader.read_parallel(&csv).unwrap()
+            );
+        }
+
+        #[test]
+        fn invalid_row_returns_err() {
+            let csv = b"n\n1\n\"a\"b\n3\n";
+            let context = ParseContext::builder().strict_quotes(true).build().unwrap();
+
+            let err = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .read_parallel(csv)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::TrailingAfterQuote { line: 3 }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod to_json {
+        use serde_json::{json, Value};
+
+        use crate::{to_json, CsvReader, DefaultSchema};
+
+        #[test]
+        fn arrays_without_headers() {
+            let rows = CsvReader::<DefaultSchema>::default()
+                .has_headers(false)
+                .read(b"a,1,,true\n")
+                .unwrap();
+
+            let json: Value = serde_json::from_str(&to_json(&rows, None)).unwrap();
+
+            assert_eq!(json!([["a", 1, null, true]]), json);
+        }
+
+        #[test]
+        fn objects_with_headers() {
+            let headers = vec!["name".to_string(), "height".to_string()];
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read(b"name,height\nfoo,1.5\nbar,,x\n")
+                .unwrap();
+
+            let json: Value = serde_json::from_str(&to_json(&rows, Some(&headers))).unwrap();
+
+            assert_eq!(
+                json!([
+                    {"name": "foo", "height": 1.5},
+                    {"name": "bar", "height": null, "2": "x"},
+                ]),
+                json
+            );
+        }
+    }
+
+    mod read_iter {
+        use crate::{
+            parser::{ConfigError, FieldValue, ParseContext, ParseError},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn rows_are_parsed_lazily() {
+            // The last row has data after a closing quote, an error with strict quotes.
+            let csv = b"n\n1\n2\n3\n\"a\"b\n";
+            let context = ParseContext::builder().strict_quotes(true).build().unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            let rows: Vec<_> = reader
+                .read_iter(csv)
+                .take(2)
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::Int(2)), rows[1].fields()[0]);
+
+            let last = reader.read_iter(csv).last().unwrap();
+            assert_eq!(Err(ParseError::TrailingAfterQuote { line: 5 }), last);
+        }
+
+        #[test]
+        fn invalid_declared_delimiter_is_the_only_item() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            let items: Vec<_> = reader.read_iter(b"sep=\"\nn\n1\n").collect();
+
+            assert_eq!(
+                vec![Err(ParseError::InvalidConfig(
+                    ConfigError::QuoteIsDelimiter(b'"')
+                ))],
+                items
+            );
+        }
+
+        #[test]
+        fn filter_rows() {
+            let csv = b"n\n1\n2\n3\n4\n";
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            let even = reader
+                .read_iter(csv)
+                .filter_map(Result::ok)
+                .filter(|r| matches!(r.fields()[0], Some(FieldValue::Int(n)) if n % 2 == 0))
+                .count();
+
+            assert_eq!(2, even);
+        }
+    }
+
+    mod read_dedup_consecutive {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        #[test]
+        fn drops_consecutive_duplicates_only() {
+            let csv = b"a,b\nx,1\nx,1\nx,1\ny,2\nx,1\ny,2\ny,2\n";
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_dedup_consecutive(csv)
+                .unwrap();
+
+            let names: Vec<_> = rows.iter().map(|r| r.fields()[0].clone()).collect();
+            let name = |s: &str| Some(FieldValue::String(s.into()));
+            assert_eq!(vec![name("x"), name("y"), name("x"), name("y")], names);
+        }
+    }
+
+    mod default_schema {
+        use crate::{parser::FieldValue, DefaultSchema};
+
+        #[test]
+        fn fields_and_len() {
+            let fields = vec![Some(FieldValue::Int(1)), None];
+
+            let row = DefaultSchema::new(fields.clone());
+
+            assert_eq!(&fields[..], row.fields());
+            assert_eq!(2, row.len());
+            assert!(!row.is_empty());
+            assert!(DefaultSchema::new(Vec::new()).is_empty());
+        }
+    }
+
+    mod has_headers {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        const CSV: &[u8] = b"a,1\nb,2\n";
+
+        fn first_values(reader: CsvReader<DefaultSchema>) -> Vec<Option<FieldValue>> {
+            reader
+                .read(CSV)
+                .unwrap()
+                .iter()
+                .map(|r| r.fields()[0].clone())
+                .collect()
+        }
+
+        #[test]
+        fn first_row_is_skipped_by_default() {
+            let reader = CsvReader::<DefaultSchema>::default().has_headers(true);
+
+            assert_eq!(
+                vec![Some(FieldValue::String("b".into()))],
+                first_values(reader)
+            );
+        }
+
+        #[test]
+        fn first_row_is_data_without_headers() {
+            let reader = CsvReader::<DefaultSchema>::default().has_headers(false);
+
+            assert_eq!(
+                vec![
+                    Some(FieldValue::String("a".into())),
+                    Some(FieldValue::String("b".into()))
+                ],
+                first_values(reader)
+            );
+        }
+    }
+
+    mod with_delimiter {
+        use crate::{
+            parser::{ConfigError, FieldValue},
+            CsvReader, DefaultSchema, NEWLINE,
+        };
+
+        fn fields(delimiter: u8, csv: &[u8]) -> Vec<Option<FieldValue>> {
+            CsvReader::<DefaultSchema>::default()
+                .with_delimiter(delimiter)
+                .unwrap()
+                .read(csv)
+                .unwrap()[0]
+                .fields()
+                .to_vec()
+        }
+
+        #[test]
+        fn comma_and_tab_delimiters() {
+            let expected = vec![
+                Some(FieldValue::String("foo".into())),
+                Some(FieldValue::Float(1.5)),
+            ];
+
+            assert_eq!(expected, fields(b',', b"name,height\nfoo,1.5\n"));
+            assert_eq!(expected, fields(b'\t', b"name\theight\nfoo\t1.5\n"));
+        }
+
+        #[test]
+        fn newline_delimiter_is_an_error() {
+            let result = CsvReader::<DefaultSchema>::default().with_delimiter(NEWLINE);
+
+            assert_eq!(Some(ConfigError::DelimiterIsNewline), result.err());
+
+            let result = CsvReader::<DefaultSchema>::default().with_delimiter(b'\r');
+            assert_eq!(Some(ConfigError::DelimiterIsNewline), result.err());
+        }
+    }
+
+    mod read_sections {
+        use crate::{
+            parser::{FieldValue, ParseContext, ParseError},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn error_lines_are_counted_from_the_start_of_the_document() {
+            let context = ParseContext::builder()
+                .strict_column_count(true)
+                .column_count(Some(2))
+                .build()
+                .unwrap();
+            let csv = b"name,n\nfoo,1\n---\nname,n\nbar,2\nbaz,3,4\n";
+
+            let err = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .read_sections(csv, b"---")
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::WrongColumnCount {
+                    line: Some(6),
+                    expected: 2,
+                    found: 3
+                }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn reads_each_section_with_its_header() {
+            let csv = b"--\nname,height\nfoo,1\nbar,2\n--\nid,score,ok\n7,0.5,yes\n--\n";
+
+            let sections = CsvReader::<DefaultSchema>::default()
+                .read_sections(csv, b"--")
+                .unwrap();
+
+            assert_eq!(2, sections.len());
+
+            let (header, rows) = &sections[0];
+            assert_eq!(&vec!["name".to_string(), "height".into()], header);
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::String("bar".into())), rows[1].fields()[0]);
+
+            let (header, rows) = &sections[1];
+            assert_eq!(&vec!["id".to_string(), "score".into(), "ok".into()], header);
+            assert_eq!(1, rows.len());
+            assert_eq!(Some(FieldValue::Float(0.5)), rows[0].fields()[1]);
+        }
+
+        #[test]
+        fn sections_use_declared_delimiter() {
+            let csv = b"sep=;\nname;height\nfoo;1\n--\nid;ok\n7;yes\n";
+
+            let sections = CsvReader::<DefaultSchema>::default()
+                .read_sections(csv, b"--")
+                .unwrap();
+
+            assert_eq!(2, sections.len());
+            assert_eq!(vec!["name".to_string(), "height".into()], sections[0].0);
+            assert_eq!(Some(FieldValue::Int(1)), sections[0].1[0].fields()[1]);
+            assert_eq!(vec!["id".to_string(), "ok".into()], sections[1].0);
+            assert_eq!(Some(FieldValue::Int(7)), sections[1].1[0].fields()[0]);
+        }
+    }
+
+    mod read_dedup_by_key {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema, Keep};
+
+        const CSV: &[u8] = b"id,v\n1,a\n2,b\n1,c\n,d\n2,e\n,f\n";
+
+        fn values(keep: Keep) -> Vec<Option<FieldValue>> {
+            CsvReader::<DefaultSchema>::default()
+                .read_dedup_by_key::<u32>(CSV, 0, keep)
+                .unwrap()
+                .iter()
+                .map(|r| r.fields()[1].clone())
+                .collect()
+        }
+
+        fn value(s: &str) -> Option<FieldValue> {
+            Some(FieldValue::String(s.into()))
+        }
+
+        #[test]
+        fn keeps_the_first_row_of_each_key() {
+            assert_eq!(
+                vec![value("a"), value("b"), value("d"), value("f")],
+                values(Keep::First)
+            );
+        }
+
+        #[test]
+        fn keeps_the_last_row_of_each_key() {
+            assert_eq!(
+                vec![value("c"), value("e"), value("d"), value("f")],
+                values(Keep::Last)
+            );
+        }
+    }
+
+    mod read_str_fields {
+        use std::borrow::Cow;
+
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn fields_are_borrowed_unless_unescaped() {
+            let csv = b"a,b\nfoo,\"x\"\"y\"\n";
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_str_fields(csv)
+                .unwrap();
+
+            assert!(matches!(rows[0][0], Cow::Borrowed("foo")));
+            assert!(matches!(&rows[0][1], Cow::Owned(s) if s == "x\"y"));
+        }
+
+        #[test]
+        fn invalid_utf8_returns_err() {
+            let result = CsvReader::<DefaultSchema>::default().read_str_fields(b"a\n\xff\n");
+
+            assert!(result.is_err());
+        }
+    }
+
+    mod read_borrowed {
+        use std::borrow::Cow;
+
+        use crate::{
+            parser::{ParseContext, ParseError},
+            CsvReader,
+        };
+
+        crate::schema!(Person<'a>, name:&'a str, city:Cow<'a, str>, age:u32);
+
+        #[test]
+        fn fields_are_borrowed_from_the_document() {
+            let csv = b"name,city,age\nfoo,\"x\"\"y\",42\n\"bar\",,7\n";
+
+            let rows = CsvReader::<Person>::default().read_borrowed(csv).unwrap();
+
+            assert_eq!(Some("foo"), rows[0].name);
+            assert!(matches!(&rows[0].city, Some(Cow::Owned(s)) if s == "x\"y"));
+            assert_eq!(Some(42), rows[0].age);
+            assert_eq!(Some("bar"), rows[1].name);
+            assert_eq!(None, rows[1].city);
+        }
+
+        #[test]
+        fn unescaped_str_field_is_none() {
+            let rows = CsvReader::<Person>::default()
+                .read_borrowed(b"name,city,age\n\"x\"\"y\",foo,1\n")
+                .unwrap();
+
+            assert_eq!(None, rows[0].name);
+            assert!(matches!(rows[0].city, Some(Cow::Borrowed("foo"))));
+        }
+
+        #[test]
+        fn rows_are_checked_in_strict_mode() {
+            let context = ParseContext::builder()
+                .strict_column_count(true)
+                .build()
+                .unwrap();
+
+            let err = CsvReader::<Person>::default()
+                .with_context(context)
+                .read_borrowed(b"name,city,age\nfoo,bar,1,2\n")
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::WrongColumnCount {
+                    line: Some(2),
+                    expected: 3,
+                    found: 4
+                }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn declared_delimiter_is_used() {
+            let rows = CsvReader::<Person>::default()
+                .read_borrowed(b"sep=;\nname;city;age\nfoo;bar;1\n")
+                .unwrap();
+
+            assert_eq!(Some("foo"), rows[0].name);
+            assert_eq!(Some(1), rows[0].age);
+        }
+
+        #[cfg(feature = "encoding")]
+        #[test]
+        fn transcoded_document_returns_err() {
+            let utf16: Vec<u8> = [0xFF, 0xFE]
+                .into_iter()
+                .chain("name\nfoo\n".encode_utf16().flat_map(u16::to_le_bytes))
+                .collect();
+
+            let err = CsvReader::<Person>::default()
+                .read_borrowed(&utf16)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::TranscodedDocument),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+    }
+
+    mod read_raw_strings {
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn returns_trimmed_fields_without_inference() {
+            let csv = b"a,b,c\n 1.50 ,true,\"x, y\"\n007\n";
+
+            let rows = CsvReader::<DefaultSchema>::default().read_raw_strings(csv);
+
+            assert_eq!(vec![vec!["1.50", "true", "x, y"], vec!["007"]], rows);
+        }
+    }
+
+    mod read_cancellable {
+        use std::sync::atomic::AtomicBool;
+
+        use crate::{parser::ParseError, CsvReader, DefaultSchema};
+
+        #[test]
+        fn returns_all_rows_when_not_cancelled() {
+            let cancel = AtomicBool::new(false);
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_cancellable(b"a\n1\n2\n", &cancel)
+                .unwrap();
+
+            assert_eq!(2, rows.len());
+        }
+
+        #[test]
+        fn returns_err_when_cancelled() {
+            let cancel = AtomicBool::new(true);
+
+            let err = CsvReader::<DefaultSchema>::default()
+                .read_cancellable(b"a\n1\n2\n", &cancel)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::Cancelled { line: 2 }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+    }
+
+    mod read_batches {
+        use crate::{
+            parser::{ConfigError, FieldValue, ParseContext, ParseError},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn yields_last_partial_batch() {
+            let csv = b"n\n1\n2\n3\n4\n5\n";
+
+            let batches: Vec<Vec<DefaultSchema>> = CsvReader::<DefaultSchema>::default()
+                .read_batches(csv, 2)
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+            assert_eq!(
+                vec![2, 2, 1],
+                batches.iter().map(Vec::len).collect::<Vec<_>>()
+            );
+            assert_eq!(Some(FieldValue::Int(5)), batches[2][0].fields()[0]);
+        }
+
+        #[test]
+        fn huge_batch_size_yields_one_batch() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            let batches: Vec<Vec<DefaultSchema>> = reader
+                .read_batches(b"n\n1\n2\n", usize::MAX)
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+            assert_eq!(vec![2], batches.iter().map(Vec::len).collect::<Vec<_>>());
+        }
+
+        #[test]
+        fn invalid_declared_delimiter_is_the_only_item() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            let items: Vec<_> = reader.read_batches(b"sep=\"\nn\n1\n", 2).collect();
+
+            assert_eq!(
+                vec![Err(ParseError::InvalidConfig(
+                    ConfigError::QuoteIsDelimiter(b'"')
+                ))],
+                items
+            );
+        }
+
+        #[test]
+        fn empty_document_yields_no_batch() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            assert_eq!(0, reader.read_batches(b"n\n", 2).count());
+        }
+
+        #[test]
+        fn yields_rows_parsed_before_error() {
+            let csv = b"n\n1\n2\n3,4\n5\n";
+            let context = ParseContext::builder()
+                .strict_column_count(true)
+                .build()
+                .unwrap();
+
+            let batches: Vec<_> = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .with_column_count(1)
+                .unwrap()
+                .read_batches(csv, 3)
+                .collect();
+
+            assert_eq!(3, batches.len());
+            assert_eq!(2, batches[0].as_ref().unwrap().len());
+            assert!(matches!(
+                batches[1],
+                Err(ParseError::WrongColumnCount { line: Some(4), .. })
+            ));
+            assert_eq!(
+                Some(FieldValue::Int(5)),
+                batches[2].as_ref().unwrap()[0].fields()[0]
+            );
+        }
+    }
+
+    mod sep_line {
+        use crate::{
+            parser::{ConfigError, FieldValue},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn read_uses_declared_delimiter_and_skips_line() {
+            let csv = b"sep=;\nname;age\nfoo;3\nbar;4\n";
+
+            let reader = CsvReader::<DefaultSchema>::default();
+            let rows = reader.read_numbered(csv).unwrap();
+
+            assert_eq!(
+                vec!["name".to_string(), "age".to_string()],
+                reader.read_headers(csv)
+            );
+            assert_eq!(2, rows.len());
+            assert_eq!(3, rows[0].0);
+            assert_eq!(Some(FieldValue::String("foo".into())), rows[0].1.fields[0]);
+            assert_eq!(Some(FieldValue::Int(3)), rows[0].1.fields[1]);
+        }
+
+        #[test]
+        fn read_skips_line_declaring_default_delimiter() {
+            let csv = b"sep=,\nname,age\nfoo,3\n";
+
+            let rows = CsvReader::<DefaultSchema>::default().read(csv).unwrap();
+
+            assert_eq!(1, rows.len());
+            assert_eq!(Some(FieldValue::Int(3)), rows[0].fields[1]);
+        }
+
+        #[test]
+        fn read_with_invalid_declared_delimiter_returns_err() {
+            let csv = b"sep=\"\nname\"age\nfoo\"3\n";
+
+            let err = CsvReader::<DefaultSchema>::default().read(csv).unwrap_err();
+
+            assert_eq!(
+                Some(&ConfigError::QuoteIsDelimiter(b'"')),
+                err.downcast_ref::<ConfigError>()
+            );
+        }
+    }
+
+    mod carriage_return_only {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        #[test]
+        fn read_returns_every_row() {
+            let csv = b"name,height\rfoo,0.5\rbar,2\r";
+
+            let rows = CsvReader::<DefaultSchema>::default().read(csv).unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::String("bar".into())), rows[1].fields[0]);
+            assert_eq!(Some(FieldValue::Int(2)), rows[1].fields[1]);
+        }
+    }
+
+    mod estimate_row_count {
+        use crate::{parser::ParseContext, CsvReader, DefaultSchema};
+
+        #[test]
+        fn uses_average_length_of_first_rows() {
+            let csv = b"name\nfoo\nbar\nbaz\n";
+
+            let context = ParseContext::default();
+
+            assert_eq!(
+                3,
+                CsvReader::<DefaultSchema>::estimate_row_count(csv, &context)
+            );
+            assert_eq!(
+                0,
+                CsvReader::<DefaultSchema>::estimate_row_count(b"name\n", &context)
+            );
+            assert_eq!(
+                0,
+                CsvReader::<DefaultSchema>::estimate_row_count(b"", &context)
+            );
+        }
+
+        #[test]
+        fn short_first_rows_do_not_overestimate() {
+            let mut csv = b"n\n".repeat(20);
+            csv.extend(vec![b'x'; 1 << 20]);
+
+            let estimate =
+                CsvReader::<DefaultSchema>::estimate_row_count(&csv, &ParseContext::default());
+
+            assert_eq!(crate::MAX_ESTIMATED_ROWS, estimate);
+        }
+
+        #[test]
+        fn read_allocates_estimated_capacity() {
+            let csv = b"name,height\nfoo,1\nbar,2\nbaz,3\nqux,4\n";
+
+            let rows = CsvReader::<DefaultSchema>::default().read(csv).unwrap();
+
+            assert_eq!(4, rows.len());
+            assert!(rows.capacity() >= 4);
+        }
+    }
+
+    mod read_reader {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        #[cfg(feature = "flate2")]
+        #[test]
+        fn detects_gzip_stream() {
+            use std::io::Write;
+
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(b"name,height\nfoo,0.5\nbar,2\n").unwrap();
+            let compressed = encoder.finish().unwrap();
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_reader(compressed.as_slice())
+                .unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::String("foo".into())), rows[0].fields[0]);
+            assert_eq!(Some(FieldValue::Int(2)), rows[1].fields[1]);
+        }
+
+        #[test]
+        fn reads_cursor() {
+            let cursor = std::io::Cursor::new(b"name,height\nfoo,0.5\nbar,2\n".to_vec());
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_reader(cursor)
+                .unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::String("bar".into())), rows[1].fields[0]);
+        }
+
+        #[test]
+        fn reads_uncompressed_stream() {
+            let data: &[u8] = b"name,height\nfoo,0.5\n";
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_reader(data)
+                .unwrap();
+
+            assert_eq!(Some(FieldValue::Float(0.5)), rows[0].fields[1]);
+        }
+    }
+
+    mod read_file {
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn empty_file_returns_no_rows() {
+            let path = std::env::temp_dir().join(format!("empty-{}.csv", std::process::id()));
+            std::fs::write(&path, b"").unwrap();
+
+            let rows = CsvReader::<DefaultSchema>::default().read_file(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(0, rows.unwrap().len());
+        }
+
+        #[test]
+        fn missing_file_returns_err() {
+            let path = std::env::temp_dir().join("missing-file-of-read-file-test.csv");
+
+            assert!(CsvReader::<DefaultSchema>::default()
+                .read_file(&path)
+                .is_err());
+        }
+    }
+
+    mod count_rows {
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn with_and_without_trailing_newline() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            assert_eq!(2, reader.count_rows(b"a,b\n1,2\n3,4\n"));
+            assert_eq!(2, reader.count_rows(b"a,b\n1,2\n3,4"));
+            assert_eq!(0, reader.count_rows(b"a,b"));
+            assert_eq!(0, reader.count_rows(b""));
+        }
+
+        #[test]
+        fn header_is_counted_without_headers() {
+            let reader = CsvReader::<DefaultSchema>::default().has_headers(false);
+
+            assert_eq!(3, reader.count_rows(b"a,b\n1,2\n3,4\n"));
+            assert_eq!(3, reader.count_rows(b"a,b\n1,2\n3,4"));
+            assert_eq!(0, reader.count_rows(b""));
+        }
+
+        #[test]
+        fn sep_line_is_not_counted() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            assert_eq!(2, reader.count_rows(b"sep=;\na;b\n1;2\n3;4\n"));
+            assert_eq!(0, reader.count_rows(b"sep=;"));
+        }
+
+        #[test]
+        fn count_rows_file() {
+            let path = std::env::temp_dir().join(format!("count-rows-{}.csv", std::process::id()));
+            std::fs::write(&path, "a,b\n1,2\n3,4").unwrap();
+
+            let count = CsvReader::<DefaultSchema>::default().count_rows_file(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(2, count.unwrap());
+        }
+    }
+
+    mod read_file_checked {
+        use std::path::Path;
+
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn rejects_unexpected_extension() {
+            let err = CsvReader::<DefaultSchema>::default()
+                .read_file_checked(Path::new("image.bin"))
+                .unwrap_err();
+
+            let err = err.downcast_ref::<std::io::Error>().unwrap();
+            assert_eq!(std::io::ErrorKind::InvalidInput, err.kind());
+            assert!(err.to_string().contains("image.bin"));
+        }
+
+        #[test]
+        fn reads_csv_file() {
+            let path =
+                std::env::temp_dir().join(format!("read-checked-{}.CSV", std::process::id()));
+            std::fs::write(&path, b"a,b\n1,2\n").unwrap();
+
+            let rows = CsvReader::<DefaultSchema>::default().read_file_checked(&path);
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(1, rows.unwrap().len());
+        }
+    }
+
+    #[cfg(feature = "zip")]
+    mod read_zip_member {
+        use std::{io::Write, path::PathBuf};
+
+        use crate::{CsvReader, DefaultSchema};
+
+        fn archive(name: &str) -> PathBuf {
+            let path = std::env::temp_dir().join(format!("{}-{}.zip", name, std::process::id()));
+            let mut writer = zip::ZipWriter::new(std::fs::File::create(&path).unwrap());
+            let options = zip::write::FileOptions::default();
+
+            writer.start_file("readme.txt", options).unwrap();
+            writer.write_all(b"not a csv").unwrap();
+            writer.start_file("data.csv", options).unwrap();
+            writer
+                .write_all(b"name,height\nfoo,0.5\nbar,1.5\n")
+                .unwrap();
+            writer.finish().unwrap();
+
+            path
+        }
+
+        #[test]
+        fn reads_named_member() {
+            let path = archive("member");
+
+            let rows = CsvReader::<DefaultSchema>::default().read_zip_member(&path, "data.csv");
+            std::fs::remove_file(&path).unwrap();
+
+            assert_eq!(2, rows.unwrap().len());
+        }
+
+        #[test]
+        fn missing_member_returns_not_found() {
+            let path = archive("missing");
+
+            let err = CsvReader::<DefaultSchema>::default()
+                .read_zip_member(&path, "other.csv")
+                .unwrap_err();
+            std::fs::remove_file(&path).unwrap();
+
+            let err = err.downcast_ref::<std::io::Error>().unwrap();
+            assert_eq!(std::io::ErrorKind::NotFound, err.kind());
+            assert!(err.to_string().contains("other.csv"));
+        }
+    }
+
+    mod read_owned {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        fn read_body(body: Vec<u8>) -> Vec<DefaultSchema> {
+            CsvReader::<DefaultSchema>::default()
+                .read_owned(body)
+                .unwrap()
+        }
+
+        #[test]
+        fn rows_outlive_buffer() {
+            let rows = read_body(b"name,height\nfoo,0.5\n".to_vec());
+
+            assert_eq!(1, rows.len());
+            assert_eq!(Some(FieldValue::String("foo".into())), rows[0].fields[0]);
+            assert_eq!(Some(FieldValue::Float(0.5)), rows[0].fields[1]);
+        }
+    }
+
+    mod read_headers {
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn quoted_header_with_delimiter() {
+            let csv = b"\"Full, Name\",age\nfoo,3\n";
+
+            let headers = CsvReader::<DefaultSchema>::default().read_headers(csv);
+
+            assert_eq!(vec!["Full, Name".to_string(), "age".to_string()], headers);
+        }
+    }
+
+    mod read_with_headers {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        #[test]
+        fn finds_values_by_column_name() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            for csv in [&b"name,age\nfoo,3\n"[..], b"age,name\n3,foo\n"] {
+                let (headers, rows) = reader.read_with_headers(csv).unwrap();
+
+                assert_eq!(Some(&FieldValue::Int(3)), rows[0].get(&headers, "age"));
+                assert_eq!(
+                    Some(&FieldValue::String("foo".into())),
+                    rows[0].get(&headers, "name")
+                );
+                assert_eq!(None, rows[0].get(&headers, "Name"));
+            }
+        }
+    }
+
+    mod read_with_index {
+        use crate::{parser::ParseError, CsvReader, DefaultSchema};
+
+        #[test]
+        fn reuses_index_across_documents() {
+            let reader = CsvReader::<DefaultSchema>::default();
+            let index = reader.header_index(b"name,age\nfoo,1\n");
+
+            let rows = reader.read_with_index(b"name,age\nbar,2\nbaz,3\n", &index);
+
+            assert_eq!(2, rows.unwrap().len());
+            assert_eq!(Some(1), index.index_of("age"));
+        }
+
+        #[test]
+        fn different_header_returns_err() {
+            let reader = CsvReader::<DefaultSchema>::default();
+            let index = reader.header_index(b"name,age\n");
+
+            let err = reader
+                .read_with_index(b"name,height\nbar,2\n", &index)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::UnexpectedHeader("name,height".into())),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+    }
+
+    mod validate_column_count {
+        use crate::{CsvReader, DefaultSchema};
+
+        #[test]
+        fn well_formed_document_returns_ok() {
+            let csv = b"a,b,c\n1,2,3\n4,,6\n";
+
+            let result = CsvReader::<DefaultSchema>::default().validate_column_count(csv, 3);
+
+            assert_eq!(Ok(()), result);
+        }
+
+        #[test]
+        fn returns_lines_with_wrong_field_count() {
+            let csv = b"a,b,c\n1,2\n4,5,6\n7,8,9,10\n";
+
+            let result = CsvReader::<DefaultSchema>::default().validate_column_count(csv, 3);
+
+            assert_eq!(Err(vec![2, 4]), result);
+        }
+    }
+
+    mod checked_schema {
+        use crate::{CheckedSchema, CsvReader};
+
+        #[test]
+        fn read_reports_invalid_fields() {
+            let csv = b"a,b\nfoo,1\n\xff,2\n";
+
+            let rows = CsvReader::<CheckedSchema>::default().read(csv).unwrap();
+
+            assert!(rows[0].invalid_columns().is_empty());
+            assert_eq!(vec![0], rows[1].invalid_columns());
+        }
+    }
+
+    mod read_columns_by_name {
+        use crate::{parser::FieldValue, CsvReader, DefaultSchema};
+
+        #[test]
+        fn transposes_rows_into_named_columns() {
+            let csv = b"name,height\nfoo,0.5\nbar\n";
+
+            let columns = CsvReader::<DefaultSchema>::default()
+                .read_columns_by_name(csv)
+                .unwrap();
+
+            assert_eq!(2, columns.len());
+            assert_eq!(
+                vec![
+                    Some(FieldValue::String("foo".into())),
+                    Some(FieldValue::String("bar".into()))
+                ],
+                columns["name"]
+            );
+            assert_eq!(vec![Some(FieldValue::Float(0.5)), None], columns["height"]);
+        }
+
+        #[test]
+        fn first_duplicate_column_wins() {
+            let csv = b"a,a\n1,2\n";
+
+            let columns = CsvReader::<DefaultSchema>::default()
+                .read_columns_by_name(csv)
+                .unwrap();
+
+            assert_eq!(1, columns.len());
+            assert_eq!(vec![Some(FieldValue::Int(1))], columns["a"]);
+        }
+    }
+
+    mod read_where {
+        use crate::{
+            parser::{FieldValue, ParseContext, ParseError},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn rows_are_checked_in_strict_mode() {
+            let context = ParseContext::builder()
+                .strict_quotes(true)
+                .strict_column_count(true)
+                .build()
+                .unwrap();
+            let csv = b"a,b\nx,5\n\"x\"y,6\ny,7\n";
+
+            let err = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .read_where(csv, "b", |_| true)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::TrailingAfterQuote { line: 3 }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn keeps_rows_matching_predicate() {
+            let csv = b"name,height\nfoo,0.5\nbar,2.5\nbaz,\nqux,3.5\n";
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_where(
+                    csv,
+                    "height",
+                    |v| matches!(v, FieldValue::Float(h) if *h > 1.0),
+                )
+                .unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(Some(FieldValue::String("bar".into())), rows[0].fields[0]);
+            assert_eq!(Some(FieldValue::String("qux".into())), rows[1].fields[0]);
+        }
+
+        #[test]
+        fn quoted_column_name_with_delimiter() {
+            let csv = b"\"Full, Name\",age\n\"Doe, John\",42\nfoo,3\n";
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read_where(
+                    csv,
+                    "Full, Name",
+                    |v| matches!(v, FieldValue::String(s) if s.contains(',')),
+                )
+                .unwrap();
+
+            assert_eq!(1, rows.len());
+            assert_eq!(Some(FieldValue::Int(42)), rows[0].fields[1]);
+        }
+
+        #[test]
+        fn unknown_column_returns_err() {
+            let csv = b"name,height\nfoo,0.5\n";
+
+            let err = CsvReader::<DefaultSchema>::default()
+                .read_where(csv, "weight", |_| true)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::UnknownColumn("weight".into())),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+    }
+
+    mod string_table {
+        use crate::{parser::FieldValue, to_string_table, DefaultSchema};
+
+        #[test]
+        fn pads_ragged_rows_and_stringifies_values() {
+            let rows = vec![
+                DefaultSchema::new(vec![
+                    Some(FieldValue::String("a".into())),
+                    None,
+                    Some(FieldValue::Float(1.5)),
+                ]),
+                DefaultSchema::new(vec![Some(FieldValue::Float(2f64))]),
+            ];
+
+            let table = to_string_table(&rows);
+
+            assert_eq!(table[0], vec!["a", "", "1.5"]);
+            assert_eq!(table[1], vec!["2", "", ""]);
+        }
+
+        #[test]
+        fn empty_input_returns_empty_table() {
+            assert!(to_string_table(&[]).is_empty());
+        }
+    }
+
+    mod coerce_column {
+        use crate::{
+            coerce_column,
+            parser::{ColumnType, FieldValue},
+            DefaultSchema,
+        };
+
+        #[test]
+        fn converts_values_and_reports_failures() {
+            let mut rows = vec![
+                DefaultSchema::new(vec![Some(FieldValue::Float(1.5))]),
+                DefaultSchema::new(vec![Some(FieldValue::String("n/a".into()))]),
+                DefaultSchema::new(vec![None]),
+                DefaultSchema::new(vec![]),
+                DefaultSchema::new(vec![Some(FieldValue::Float(3f64))]),
+            ];
+
+            let failed = coerce_column(&mut rows, 0, ColumnType::String);
+            assert!(failed.is_empty());
+            assert_eq!(Some(FieldValue::String("1.5".into())), rows[0].fields()[0]);
+
+            let failed = coerce_column(&mut rows, 0, ColumnType::Int);
+            assert_eq!(vec![0, 1], failed);
+            assert_eq!(Some(FieldValue::String("1.5".into())), rows[0].fields()[0]);
+            assert_eq!(None, rows[2].fields()[0]);
+            assert_eq!(Some(FieldValue::Int(3)), rows[4].fields()[0]);
+        }
+    }
+
+    mod csv_enum {
+        use crate::{
+            parser::{FieldParser, ParseContext, ParseError},
+            CsvReader,
+        };
+
+        csv_enum!(pub Status { Active = "active", Inactive = "inactive", Other(String) });
+        csv_enum!(Color { Red = "red", Blue = "blue" });
+
+        schema!(Account, name:String, status:Status);
+
+        #[test]
+        fn parse_known_label() {
+            assert_eq!(Status::Active, StatusParser::parse(b"active").unwrap());
+            assert_eq!(Status::Inactive, StatusParser::parse(b" inactive").unwrap());
+        }
+
+        #[test]
+        fn parse_unknown_label_populates_catch_all() {
+            assert_eq!(
+                Status::Other("suspended".to_string()),
+                StatusParser::parse(b"suspended").unwrap()
+            );
+        }
+
+        #[test]
+        fn catch_all_stores_the_trimmed_value() {
+            assert_eq!(
+                Status::Other("suspended".to_string()),
+                StatusParser::parse(b" suspended\t").unwrap()
+            );
+        }
+
+        #[test]
+        fn labels_are_trimmed_with_the_context() {
+            let context = ParseContext::builder()
+                .ascii_trim(true)
+                .trim_chars(Some(b"*"))
+                .build()
+                .unwrap();
+
+            assert_eq!(
+                Status::Active,
+                StatusParser::parse_with_context(b"*active*", &context).unwrap()
+            );
+            assert_eq!(
+                Status::Other("\u{a0}active".to_string()),
+                StatusParser::parse_with_context(" \u{a0}active".as_bytes(), &context).unwrap()
+            );
+        }
+
+        #[test]
+        fn parse_unknown_label_without_catch_all_returns_err() {
+            let err = ColorParser::parse(b"green").unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::UnknownLabel("green".to_string())),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn parse_schema_with_enum_field() {
+            let csv = b"name,status\nfoo,active\nbar,banned\n";
+
+            let rows = CsvReader::<Account>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].status, Some(Status::Active));
+            assert_eq!(rows[1].status, Some(Status::Other("banned".to_string())));
+        }
+    }
+
+    mod schema {
+        use crate::{
+            parser::{
+                CoordParser, CurrencyParser, ListParser, LookupParser, ParseContext, ParseError,
+                RowParser, Unmapped,
+            },
+            CsvReader, CsvWriter, DefaultSchema, ExpectedHeaders, HeaderComparison,
+        };
+
+        schema!(pub MySchema, name:String, height:f64);
+        schema!(pub Post, title:String, tags:Vec<String> = ListParser::<String>::new(b'|'));
+        schema!(pub Blob, name:String, blob:Vec<u8>);
+        schema!(pub Invoice, id:String, total:f64 = CurrencyParser::new("$"));
+        schema!(pub Grade, name:String, score:f64 = LookupParser::from_pairs([("A", 4.0), ("B", 3.0)], Unmapped::Passthrough));
+        schema!(pub Record, id:std::num::NonZeroU64, name:String);
+        schema!(pub Place, name:String, loc:(f64, f64) = CoordParser);
+        schema!(pub Counter, id:i64, count:u32);
+
+        #[test]
+        fn parse_coordinate_field_with_parser_override() {
+            let csv = b"name,loc\nsf,\"37.77,-122.41\"\nnowhere,\"north,west\"\n";
+
+            let rows = CsvReader::<Place>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].loc, Some((37.77, -122.41)));
+            assert_eq!(rows[1].loc, None);
+        }
+
+        #[test]
+        fn parse_rows_with_trailing_delimiter() {
+            let rows = CsvReader::<MySchema>::default()
+                .read(b"name,height\nfoo,\n,\nbar\n")
+                .unwrap();
+
+            assert_eq!(rows[0].name, Some("foo".into()));
+            assert_eq!(rows[0].height, None);
+            assert_eq!(rows[1], MySchema::default());
+            assert_eq!(rows[2].name, Some("bar".into()));
+            assert_eq!(rows[2].height, None);
+
+            let rows = CsvReader::<Post>::default()
+                .read(b"title,tags\nfoo,a|b\nbar,\n")
+                .unwrap();
+
+            assert_eq!(rows[1].title, Some("bar".into()));
+            assert_eq!(rows[1].tags, None);
+        }
+
+        #[test]
+        fn quoted_empty_is_empty_string_and_unquoted_empty_is_none() {
+            let rows = CsvReader::<MySchema>::default()
+                .read(b"name,height\n\"\",1\n,2\n")
+                .unwrap();
+
+            assert_eq!(rows[0].name, Some(String::new()));
+            assert_eq!(rows[1].name, None);
+        }
+
+        #[test]
+        fn empty_as_null_disabled_parses_unquoted_empty_fields() {
+            let context = ParseContext::builder()
+                .empty_as_null(false)
+                .build()
+                .unwrap();
+
+            let rows = CsvReader::<MySchema>::default()
+                .with_context(context)
+                .read(b"name,height\n\"\",1\n,2\n")
+                .unwrap();
+
+            assert_eq!(rows[0].name, Some(String::new()));
+            assert_eq!(rows[1].name, Some(String::new()));
+        }
+
+        #[test]
+        fn read_by_name_and_write_in_original_order() {
+            let csv = b"height,id,name\n1.5,7,foo\n2.5,8,bar\n";
+
+            let (order, mut rows) = CsvReader::<MySchema>::default().read_by_name(csv).unwrap();
+
+            assert_eq!(rows[0].row.name, Some("foo".into()));
+            assert_eq!(rows[0].row.height, Some(1.5));
+            assert_eq!(vec!["7"], rows[0].unmapped);
+            assert_eq!(&[Some(2), Some(0)], order.positions());
+
+            rows[1].row.height = Some(3.5);
+
+            let mut writer = CsvWriter::new(Vec::new());
+            writer.write_header(order.names()).unwrap();
+            for crate::MappedRow { row, unmapped } in &rows {
+                let name = row.name.clone().unwrap_or_default();
+                let height = row.height.map(|h| h.to_string()).unwrap_or_default();
+                writer
+                    .write_mapped(&order, [name, height], unmapped)
+                    .unwrap();
+            }
+            writer.write_record(["plain", "record"]).unwrap();
+
+            // The id column is not a field of the schema, but keeps its values.
+            assert_eq!(
+                b"height,id,name\n1.5,7,foo\n3.5,8,bar\nplain,record\n".to_vec(),
+                writer.into_inner()
+            );
+        }
+
+        #[test]
+        fn read_by_name_checks_rows_against_the_header() {
+            let context = ParseContext::builder()
+                .strict_column_count(true)
+                .build()
+                .unwrap();
+            let reader = CsvReader::<MySchema>::default().with_context(context);
+
+            let err = reader
+                .read_by_name(b"name,id,height\na,1,0.5\nb,2,extra,1\n")
+                .unwrap_err();
+            assert_eq!(
+                Some(&ParseError::WrongColumnCount {
+                    line: Some(3),
+                    expected: 3,
+                    found: 4
+                }),
+                err.downcast_ref::<ParseError>()
+            );
+
+            // The id column is not a field of the schema, but is part of the header.
+            assert!(reader.read_by_name(b"name,id,height\na,1,0.5\n").is_ok());
+        }
+
+        #[test]
+        fn parse_integer_fields() {
+            let csv = b"id,count\n-7, 12\n8,-1\n";
+
+            let rows = CsvReader::<Counter>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].id, Some(-7));
+            assert_eq!(rows[0].count, Some(12));
+            assert_eq!(rows[1].id, Some(8));
+            assert_eq!(rows[1].count, None);
+        }
+
+        #[test]
+        fn parse_non_zero_field() {
+            let rows = CsvReader::<Record>::default()
+                .read(b"id,name\n12,foo\n,bar\n0,baz\n")
+                .unwrap();
+
+            assert_eq!(rows[0].id, std::num::NonZeroU64::new(12));
+            assert_eq!(rows[1].id, None);
+            assert_eq!(rows[2].id, None);
+            assert_eq!(
+                std::mem::size_of::<u64>(),
+                std::mem::size_of::<Option<std::num::NonZeroU64>>()
+            );
+        }
+
+        #[test]
+        fn parse_lookup_field_with_parser_override() {
+            let csv = b"name,score\nfoo,A\nbar,2.5\nbaz,Z\n";
+
+            let rows = CsvReader::<Grade>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].score, Some(4.0));
+            assert_eq!(rows[1].score, Some(2.5));
+            assert_eq!(rows[2].score, None);
+        }
+
+        #[test]
+        fn parse_currency_field_with_parser_override() {
+            let csv = b"id,total\na,\"$1,234.50\"\nb,($3)\n";
+            let context = ParseContext::builder()
+                .grouping_separator(Some(b','))
+                .build()
+                .unwrap();
+
+            let rows = CsvReader::<Invoice>::default()
+                .with_context(context)
+                .read(csv)
+                .unwrap();
+
+            assert_eq!(rows[0].total, Some(1234.5));
+            assert_eq!(rows[1].total, Some(-3f64));
+        }
+
+        #[test]
+        fn parse_bytes_field_without_utf8_validation() {
+            let csv = b"name,blob\nfoo,\xde\xad\n";
+
+            let rows = CsvReader::<Blob>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].name, Some("foo".to_string()));
+            assert_eq!(rows[0].blob, Some(vec![0xde, 0xad]));
+        }
+
+        #[test]
+        fn parse_file() {
+            let csv = b"header1,header2\nfoo1,0.32\nfoo2,1\n";
+
+            let rows = CsvReader::<MySchema>::default().read(csv).unwrap();
+            assert_eq!(rows.len(), 2);
+
+            assert_eq!(rows[0].name, Some("foo1".to_string()));
+            assert_eq!(rows[0].height, Some(0.32f64));
+
+            assert_eq!(rows[1].name, Some("foo2".to_string()));
+            assert_eq!(rows[1].height, Some(1f64));
+        }
+
+        #[test]
+        fn parse_file_with_context() {
+            let csv = b"header1;header2\nfoo1;0,32\nfoo2;1\n";
+
+            let context = ParseContext::builder()
+                .delimiter(b';')
+                .decimal_separator(b',')
+                .build()
+                .unwrap();
+
+            let rows = CsvReader::<MySchema>::default()
+                .with_context(context)
+                .read(csv)
+                .unwrap();
+            assert_eq!(rows.len(), 2);
+
+            assert_eq!(rows[0].name, Some("foo1".to_string()));
+            assert_eq!(rows[0].height, Some(0.32f64));
+
+            assert_eq!(rows[1].name, Some("foo2".to_string()));
             assert_eq!(rows[1].height, Some(1f64));
         }
 
+        #[test]
+        fn parse_file_with_comma_context() {
+            let csv = b"header1,header2\n\"foo, bar\",0.5\n";
+
+            let context = ParseContext::builder().delimiter(b',').build().unwrap();
+
+            let rows = CsvReader::<MySchema>::default()
+                .with_context(context)
+                .read(csv)
+                .unwrap();
+
+            assert_eq!(rows[0].name, Some("foo, bar".to_string()));
+            assert_eq!(rows[0].height, Some(0.5f64));
+        }
+
+        #[test]
+        fn parse_file_with_parser_override() {
+            let csv = b"title,tags\nfoo,\"a|b|c\"\nbar,d\n";
+
+            let rows = CsvReader::<Post>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].title, Some("foo".to_string()));
+            assert_eq!(
+                rows[0].tags,
+                Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+            );
+            assert_eq!(rows[1].tags, Some(vec!["d".to_string()]));
+        }
+
+        #[test]
+        fn read_keeps_trailing_data_after_quote_by_default() {
+            let csv = b"header1,header2\n\"quoted\"garbage,1\n";
+
+            let rows = CsvReader::<MySchema>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].name, Some("quotedgarbage".to_string()));
+            assert_eq!(rows[0].height, Some(1f64));
+        }
+
+        #[test]
+        fn read_with_strict_quotes_returns_err_on_trailing_data() {
+            let csv = b"header1,header2\n\"ok\",1\n\"quoted\"garbage,1\n";
+
+            let context = ParseContext::builder().strict_quotes(true).build().unwrap();
+
+            let err = CsvReader::<MySchema>::default()
+                .with_context(context)
+                .read(csv)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::TrailingAfterQuote { line: 3 }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn strict_checks_cover_fields_after_the_schema() {
+            let csv = b"header1,header2\nfoo,1,\"quoted\"garbage\n";
+            let context = ParseContext::builder()
+                .strict_quotes(true)
+                .strict_column_count(true)
+                .build()
+                .unwrap();
+            let reader = CsvReader::<MySchema>::default().with_context(context);
+
+            for err in [
+                reader.read(csv).unwrap_err(),
+                reader.read_strict(csv).unwrap_err(),
+            ] {
+                assert_eq!(
+                    Some(&ParseError::TrailingAfterQuote { line: 2 }),
+                    err.downcast_ref::<ParseError>()
+                );
+            }
+        }
+
+        #[test]
+        fn read_quoted_fields() {
+            let csv = b"name,height\n\"Smith, John\",1\n\"multi\nline\",2\n\"open,3\n";
+
+            let rows = CsvReader::<MySchema>::default().read(csv).unwrap();
+
+            assert_eq!(3, rows.len());
+            assert_eq!(rows[0].name, Some("Smith, John".to_string()));
+            assert_eq!(rows[0].height, Some(1f64));
+            assert_eq!(rows[1].name, Some("multi\nline".to_string()));
+            assert_eq!(rows[1].height, Some(2f64));
+            // An unterminated quoted field is the rest of the document.
+            assert_eq!(rows[2].name, Some("open,3\n".to_string()));
+            assert_eq!(rows[2].height, None);
+        }
+
+        #[test]
+        fn read_strict_returns_err_on_invalid_field() {
+            let csv = b"name,height\nfoo,1\nbar,\nbaz,tall\n";
+
+            let rows = CsvReader::<MySchema>::default().read(csv).unwrap();
+            assert_eq!(rows[2].height, None);
+
+            let err = CsvReader::<MySchema>::default()
+                .read_strict(csv)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::InvalidField {
+                    line: Some(4),
+                    column: 1,
+                    message: "error while parsing a float".into()
+                }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn read_strict_keeps_empty_fields() {
+            let csv = b"name,height\nfoo,1\nbar,\n";
+
+            let rows = CsvReader::<MySchema>::default().read_strict(csv).unwrap();
+
+            assert_eq!(rows[1].name, Some("bar".to_string()));
+            assert_eq!(rows[1].height, None);
+        }
+
+        #[test]
+        fn read_strict_default_schema() {
+            let csv = b"a,b\n1,x\n2,\xff\n";
+
+            let err = CsvReader::<DefaultSchema>::default()
+                .read_strict(csv)
+                .unwrap_err();
+
+            assert!(matches!(
+                err.downcast_ref::<ParseError>(),
+                Some(ParseError::InvalidField {
+                    line: Some(3),
+                    column: 1,
+                    ..
+                })
+            ));
+        }
+
+        #[test]
+        fn read_ignores_extra_fields_by_default() {
+            let csv = b"header1,header2\nfoo,1,extra,\n";
+
+            let rows = CsvReader::<MySchema>::default().read(csv).unwrap();
+
+            assert_eq!(rows[0].name, Some("foo".to_string()));
+            assert_eq!(rows[0].height, Some(1f64));
+        }
+
+        #[test]
+        fn read_with_strict_column_count_returns_err_on_extra_fields() {
+            let csv = b"header1,header2\nfoo,1\nbar\nbaz,2,extra\n";
+
+            let context = ParseContext::builder()
+                .strict_column_count(true)
+                .build()
+                .unwrap();
+
+            let err = CsvReader::<MySchema>::default()
+                .with_context(context)
+                .read(csv)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::WrongColumnCount {
+                    line: Some(4),
+                    expected: 2,
+                    found: 3
+                }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn read_numbered() {
+            let csv = b"header1,header2\nfoo1,0.32\nfoo2,1\n";
+
+            let rows = CsvReader::<MySchema>::default().read_numbered(csv).unwrap();
+
+            assert_eq!(2, rows.len());
+            assert_eq!(2, rows[0].0);
+            assert_eq!(Some("foo1".to_string()), rows[0].1.name);
+            assert_eq!(3, rows[1].0);
+            assert_eq!(Some("foo2".to_string()), rows[1].1.name);
+        }
+
+        #[test]
+        fn expected_headers() {
+            assert_eq!(
+                &["name", "height"],
+                <MySchema as ExpectedHeaders>::expected_headers()
+            );
+        }
+
+        #[test]
+        fn verify_header() {
+            let reader = CsvReader::<MySchema>::default();
+
+            assert_eq!(
+                Ok(()),
+                reader.verify_header(b"name,height\nfoo,1\n", HeaderComparison::default())
+            );
+
+            let mismatch = reader
+                .verify_header(b"name,weight\nfoo,1\n", HeaderComparison::default())
+                .unwrap_err();
+            assert_eq!(vec!["height".to_string()], mismatch.missing);
+            assert_eq!(vec!["weight".to_string()], mismatch.extra);
+        }
+
+        #[test]
+        fn verify_header_ignoring_case_and_order() {
+            let comparison = HeaderComparison {
+                ignore_order: true,
+                ignore_case: true,
+            };
+
+            let result =
+                CsvReader::<MySchema>::default().verify_header(b"Height,NAME\n", comparison);
+
+            assert_eq!(Ok(()), result);
+        }
+
+        #[test]
+        fn sample_rows() {
+            let mut csv = b"name,height\n".to_vec();
+            for i in 0..100 {
+                csv.extend_from_slice(format!("foo{},{}\n", i, i).as_bytes());
+            }
+
+            let reader = CsvReader::<MySchema>::default();
+            let rows = reader.sample_rows(&csv, 5, 1).unwrap();
+
+            assert_eq!(5, rows.len());
+            assert!(rows.windows(2).all(|w| w[0].height < w[1].height));
+            assert_eq!(rows, reader.sample_rows(&csv, 5, 1).unwrap());
+        }
+
+        #[test]
+        fn sample_rows_with_huge_n_returns_all_rows() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            assert_eq!(
+                2,
+                reader
+                    .sample_rows(b"a\n1\n2\n", usize::MAX, 1)
+                    .unwrap()
+                    .len()
+            );
+        }
+
         #[test]
         fn schema() {
             let context = ParseContext::default();
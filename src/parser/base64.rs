@@ -0,0 +1,54 @@
+use std::error::Error;
+
+use ::base64::{engine::general_purpose::STANDARD, Engine};
+
+use super::{FieldParser, RowSpan};
+
+/// Parses a field containing base64-encoded binary data, such as `3q0=`, into bytes.
+/// Whitespace around the value is ignored, and invalid base64 is an error.
+///
+/// Use the [super::BytesParser] for fields that contain the raw bytes.
+pub struct Base64Parser;
+
+impl FieldParser<Vec<u8>> for Base64Parser {
+    fn parse(span: &RowSpan) -> Result<Vec<u8>, Box<dyn Error>> {
+        match STANDARD.decode(span.trim_ascii()) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        parser::{Base64Parser, FieldParser},
+        schema, CsvReader,
+    };
+
+    schema!(pub Message, id:u32, payload:Vec<u8> = Base64Parser);
+
+    #[test]
+    fn parse_valid_base64_returns_ok() {
+        assert_eq!(
+            vec![0xde, 0xad, 0xbe],
+            Base64Parser::parse(b" 3q2+ ").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_base64_returns_err() {
+        assert!(Base64Parser::parse(b"3q2*").is_err());
+        assert!(Base64Parser::parse(b"3q2").is_err());
+    }
+
+    #[test]
+    fn parse_base64_field_with_parser_override() {
+        let csv = b"id,payload\n1,3q0=\n2,not base64\n";
+
+        let rows = CsvReader::<Message>::default().read(csv).unwrap();
+
+        assert_eq!(rows[0].payload, Some(vec![0xde, 0xad]));
+        assert_eq!(rows[1].payload, None);
+    }
+}
@@ -0,0 +1,193 @@
+use std::{borrow::Cow, error::Error};
+
+use super::{
+    check_fields, FieldParser, FieldSpan, IntoFieldParser, ParseContext, ParseError, RowSpan,
+    RowSpanIterator,
+};
+
+/// Parses a field into a value that can borrow from the document, such as a `&'a str`.
+/// The field is borrowed from the document, unless it had to be unescaped.
+///
+/// Every [FieldParser] is also a [BorrowedFieldParser].
+pub trait BorrowedFieldParser<'a, T> {
+    fn parse_borrowed(
+        span: Cow<'a, FieldSpan>,
+        context: &ParseContext,
+    ) -> Result<T, Box<dyn Error>>;
+}
+
+impl<'a, T, P: FieldParser<T>> BorrowedFieldParser<'a, T> for P {
+    fn parse_borrowed(
+        span: Cow<'a, FieldSpan>,
+        context: &ParseContext,
+    ) -> Result<T, Box<dyn Error>> {
+        P::parse_with_context(&span, context)
+    }
+}
+
+/// The [BorrowedFieldParser] of a type, used by the fields of a [crate::schema] declared
+/// with a lifetime. Every type with an [IntoFieldParser] has one.
+pub trait IntoBorrowedFieldParser<'a, T> {
+    type Parser: BorrowedFieldParser<'a, T>;
+}
+
+impl<'a, T: IntoFieldParser<T>> IntoBorrowedFieldParser<'a, T> for T {
+    type Parser = T::Parser;
+}
+
+impl<'a> IntoBorrowedFieldParser<'a, &'a str> for &'a str {
+    type Parser = StrParser;
+}
+
+impl<'a> IntoBorrowedFieldParser<'a, Cow<'a, str>> for Cow<'a, str> {
+    type Parser = CowStrParser;
+}
+
+/// Parses a field into a `&str` borrowed from the document, without copying it. The field
+/// is validated as UTF-8, and the trim characters of the context, if any, are removed.
+///
+/// A field that had to be unescaped, such as `"x""y"`, is not part of the document and
+/// fails with [ParseError::UnescapedField]: use a `Cow<str>` to read such fields, see
+/// [CowStrParser].
+pub struct StrParser;
+
+impl<'a> BorrowedFieldParser<'a, &'a str> for StrParser {
+    fn parse_borrowed(
+        span: Cow<'a, FieldSpan>,
+        context: &ParseContext,
+    ) -> Result<&'a str, Box<dyn Error>> {
+        match span {
+            Cow::Borrowed(span) => Ok(std::str::from_utf8(context.trim_field(span))?),
+            Cow::Owned(span) if span.is_empty() => Ok(""),
+            Cow::Owned(_) => Err(ParseError::UnescapedField.into()),
+        }
+    }
+}
+
+/// Like [StrParser], but the fields that had to be unescaped are allocated instead of
+/// failing.
+pub struct CowStrParser;
+
+impl<'a> BorrowedFieldParser<'a, Cow<'a, str>> for CowStrParser {
+    fn parse_borrowed(
+        span: Cow<'a, FieldSpan>,
+        context: &ParseContext,
+    ) -> Result<Cow<'a, str>, Box<dyn Error>> {
+        match span {
+            Cow::Borrowed(span) => Ok(Cow::Borrowed(std::str::from_utf8(
+                context.trim_field(span),
+            )?)),
+            Cow::Owned(span) => Ok(Cow::Owned(String::from_utf8(
+                context.trim_field(&span).to_vec(),
+            )?)),
+        }
+    }
+}
+
+/// Parses the field with the [BorrowedFieldParser] of `T`, returning `None` if it cannot
+/// be parsed. Used by [crate::schema].
+pub fn try_parse_borrowed<'a, T: IntoBorrowedFieldParser<'a, T>>(
+    span: Cow<'a, FieldSpan>,
+    context: &ParseContext,
+) -> Option<T> {
+    <T as IntoBorrowedFieldParser<'a, T>>::Parser::parse_borrowed(span, context).ok()
+}
+
+/// Returns the part of `outer` that `inner` is a slice of, with the lifetime of `outer`.
+///
+/// The fields returned by a [RowSpanIterator] only live as long as its context, which is
+/// often shorter than the document they are sliced from.
+///
+/// # Panics
+///
+/// Panics if `inner` is not a slice of `outer`.
+pub(crate) fn reborrow<'a>(outer: &'a [u8], inner: &[u8]) -> &'a [u8] {
+    let start = (inner.as_ptr() as usize)
+        .checked_sub(outer.as_ptr() as usize)
+        .expect("the slice is part of the document");
+    &outer[start..start + inner.len()]
+}
+
+/// Returns the field of the row with the lifetime of the row, rather than the lifetime of
+/// the [RowSpanIterator] that split it. Used by [crate::schema].
+pub fn borrow_field<'a>(row: &'a RowSpan, field: Cow<'_, FieldSpan>) -> Cow<'a, FieldSpan> {
+    match field {
+        Cow::Borrowed(span) => Cow::Borrowed(reborrow(row, span)),
+        Cow::Owned(span) => Cow::Owned(span),
+    }
+}
+
+/// A [super::RowParser] of schemas whose fields can borrow from the document, see
+/// [crate::CsvReader::read_borrowed].
+pub trait BorrowedRowParser<'a, S> {
+    fn parse_borrowed(row: &'a RowSpan, context: &ParseContext) -> S;
+
+    /// The number of fields of the schema, or `None` if any number of fields is read.
+    fn field_count() -> Option<usize> {
+        None
+    }
+
+    /// Parses the row found at the line (1-based), and checks it if enabled by the
+    /// context, like [super::RowParser::parse_checked].
+    fn parse_borrowed_checked(
+        row: &'a RowSpan,
+        line: usize,
+        context: &ParseContext,
+    ) -> Result<S, ParseError> {
+        check_fields(
+            RowSpanIterator::new(context, row),
+            Self::field_count(),
+            line,
+        )?;
+        Ok(Self::parse_borrowed(row, context))
+    }
+}
+
+pub trait IntoBorrowedRowParser<'a, S> {
+    type Parser: BorrowedRowParser<'a, S>;
+}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use crate::parser::{
+        borrowed::try_parse_borrowed, BorrowedFieldParser, ParseContext, RowSpanIterator, StrParser,
+    };
+
+    #[test]
+    fn str_is_borrowed_from_the_row() {
+        let context = ParseContext::default();
+        let row = b"foo,\"bar\"";
+
+        let fields: Vec<Option<&str>> = RowSpanIterator::new(&context, row)
+            .map(|field| try_parse_borrowed(super::borrow_field(row, field), &context))
+            .collect();
+
+        assert_eq!(vec![Some("foo"), Some("bar")], fields);
+    }
+
+    #[test]
+    fn unescaped_field_is_an_error_for_str_but_not_for_cow() {
+        let context = ParseContext::default();
+        let field = RowSpanIterator::new(&context, b"\"x\"\"y\"")
+            .next()
+            .unwrap();
+
+        assert!(StrParser::parse_borrowed(field.clone(), &context).is_err());
+        assert_eq!(
+            Some(Cow::<str>::Owned("x\"y".into())),
+            try_parse_borrowed(field, &context)
+        );
+    }
+
+    #[test]
+    fn other_types_use_their_field_parser() {
+        let context = ParseContext::default();
+
+        assert_eq!(
+            Some(42u32),
+            try_parse_borrowed(Cow::Borrowed(&b"42"[..]), &context)
+        );
+    }
+}
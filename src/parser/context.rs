@@ -0,0 +1,1019 @@
+use std::{borrow::Cow, error::Error};
+
+use crate::{COMMA, NEWLINE};
+
+use super::{ColumnType, TypeInference};
+
+/// The characters used to write numbers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormat {
+    /// The character separating the integral part from the fractional part.
+    /// Defaults to `.`.
+    pub decimal_separator: u8,
+    /// The character separating groups of thousands, if any, such as `,` in `1,000`.
+    pub grouping_separator: Option<u8>,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator: b'.',
+            grouping_separator: None,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// Rewrites the number in the format expected by Rust: without grouping separators,
+    /// and with `.` as the decimal separator.
+    pub fn normalize<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let grouping = self.grouping_separator.map(|c| c as char);
+        let decimal = self.decimal_separator as char;
+
+        if grouping.is_none_or(|g| !s.contains(g)) && (decimal == '.' || !s.contains(decimal)) {
+            return Cow::Borrowed(s);
+        }
+
+        s.chars()
+            .filter(|c| Some(*c) != grouping)
+            .map(|c| if c == decimal { '.' } else { c })
+            .collect()
+    }
+
+    /// Returns true if the number follows the exact grammar of this format: an optional
+    /// sign, digits whose groups of thousands may be separated by the grouping separator,
+    /// an optional decimal separator followed by digits, and an optional exponent.
+    pub fn is_well_formed(&self, s: &str) -> bool {
+        fn unsigned(s: &[u8]) -> &[u8] {
+            match s.first() {
+                Some(b'+' | b'-') => &s[1..],
+                _ => s,
+            }
+        }
+
+        let s = s.as_bytes();
+        let digits = |s: &[u8]| s.iter().take_while(|c| c.is_ascii_digit()).count();
+
+        let s = unsigned(s);
+        let (mantissa, exponent) = match s.iter().position(|c| *c == b'e' || *c == b'E') {
+            Some(i) => (&s[..i], Some(unsigned(&s[i + 1..]))),
+            None => (s, None),
+        };
+        let (integral, fraction) = match mantissa.iter().position(|c| *c == self.decimal_separator)
+        {
+            Some(i) => (&mantissa[..i], Some(&mantissa[i + 1..])),
+            None => (mantissa, None),
+        };
+
+        let all_digits = |s: &[u8]| !s.is_empty() && digits(s) == s.len();
+        let integral_ok = match self.grouping_separator {
+            Some(g) if integral.contains(&g) => {
+                let mut groups = integral.split(|c| *c == g);
+                let first = groups.next().unwrap_or_default();
+                all_digits(first)
+                    && first.len() <= 3
+                    && groups.all(|g| g.len() == 3 && all_digits(g))
+            }
+            _ => all_digits(integral),
+        };
+
+        integral_ok && fraction.is_none_or(all_digits) && exponent.is_none_or(all_digits)
+    }
+}
+
+/// The labels of boolean values, matched case-insensitively by [crate::parser::BoolParser].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoolStyle {
+    /// `true` and `false`.
+    TrueFalse,
+    /// `yes` and `no`, or `y` and `n`.
+    YesNo,
+    /// `1` and `0`.
+    OneZero,
+    /// `t` and `f`.
+    TF,
+}
+
+impl BoolStyle {
+    /// Returns the labels of `true` and of `false`, in lowercase.
+    pub fn labels(&self) -> (&'static [&'static str], &'static [&'static str]) {
+        match self {
+            BoolStyle::TrueFalse => (&["true"], &["false"]),
+            BoolStyle::YesNo => (&["yes", "y"], &["no", "n"]),
+            BoolStyle::OneZero => (&["1"], &["0"]),
+            BoolStyle::TF => (&["t"], &["f"]),
+        }
+    }
+
+    /// Returns the value of the label, if it is one of the labels of this style.
+    pub fn parse(&self, label: &str) -> Option<bool> {
+        let (truthy, falsy) = self.labels();
+
+        if truthy.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            Some(true)
+        } else if falsy.iter().any(|l| l.eq_ignore_ascii_case(label)) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+/// What to do with the comment lines of a document, see [ParseContextBuilder::comment].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentMode {
+    /// Comment lines are dropped.
+    #[default]
+    Skip,
+    /// Comment lines are kept apart from the rows, see [crate::CsvReader::read_with_comments].
+    Collect,
+}
+
+/// What a delimiter at the end of a row stands for, see
+/// [ParseContextBuilder::trailing_delimiter].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrailingDelimiter {
+    /// The delimiter is followed by an empty field, so that `a,b,` has three fields.
+    #[default]
+    EmptyField,
+    /// The delimiter ends the row, so that `a,b,` has two fields.
+    Ignore,
+}
+
+/// The settings used to parse a CSV document.
+#[derive(Debug, Clone)]
+pub struct ParseContext {
+    pub(crate) delimiter: u8,
+    pub(crate) number_format: NumberFormat,
+    pub(crate) skip_initial_space: bool,
+    pub(crate) quote: Option<u8>,
+    pub(crate) whitespace_as_empty: bool,
+    pub(crate) trim_cr: bool,
+    pub(crate) strict_quotes: bool,
+    pub(crate) strict_column_count: bool,
+    pub(crate) column_count: Option<usize>,
+    pub(crate) record_terminator: Option<u8>,
+    pub(crate) bool_style: Option<BoolStyle>,
+    pub(crate) quoted_as_string: bool,
+    pub(crate) quote_anywhere: bool,
+    pub(crate) strict_numeric: bool,
+    pub(crate) trim_chars: Option<Vec<u8>>,
+    pub(crate) ascii_trim: bool,
+    pub(crate) header_rows: usize,
+    pub(crate) header_separator: String,
+    pub(crate) escape: Option<u8>,
+    pub(crate) header_delimiter: Option<u8>,
+    pub(crate) comment: Option<u8>,
+    pub(crate) comment_mode: CommentMode,
+    pub(crate) type_inference: TypeInference,
+    pub(crate) integers_as_float: bool,
+    pub(crate) empty_as_null: bool,
+    pub(crate) skip_empty_rows: bool,
+    pub(crate) skip_delimiter_only_rows: bool,
+    pub(crate) trailing_delimiter: TrailingDelimiter,
+}
+
+impl Default for ParseContext {
+    fn default() -> Self {
+        Self {
+            delimiter: COMMA,
+            number_format: NumberFormat::default(),
+            skip_initial_space: false,
+            quote: Some(b'"'),
+            whitespace_as_empty: false,
+            trim_cr: true,
+            strict_quotes: false,
+            strict_column_count: false,
+            column_count: None,
+            record_terminator: None,
+            bool_style: None,
+            quoted_as_string: false,
+            quote_anywhere: false,
+            strict_numeric: false,
+            trim_chars: None,
+            ascii_trim: false,
+            header_rows: 1,
+            header_separator: " / ".to_string(),
+            escape: None,
+            header_delimiter: None,
+            comment: None,
+            comment_mode: CommentMode::default(),
+            type_inference: TypeInference::default(),
+            integers_as_float: false,
+            empty_as_null: true,
+            skip_empty_rows: false,
+            skip_delimiter_only_rows: false,
+            trailing_delimiter: TrailingDelimiter::default(),
+        }
+    }
+}
+
+impl ParseContext {
+    pub fn builder() -> ParseContextBuilder {
+        ParseContextBuilder::default()
+    }
+
+    pub fn delimiter(&self) -> u8 {
+        self.delimiter
+    }
+
+    pub fn number_format(&self) -> &NumberFormat {
+        &self.number_format
+    }
+
+    pub fn skip_initial_space(&self) -> bool {
+        self.skip_initial_space
+    }
+
+    pub fn quote(&self) -> Option<u8> {
+        self.quote
+    }
+
+    pub fn whitespace_as_empty(&self) -> bool {
+        self.whitespace_as_empty
+    }
+
+    pub fn trim_cr(&self) -> bool {
+        self.trim_cr
+    }
+
+    pub fn strict_quotes(&self) -> bool {
+        self.strict_quotes
+    }
+
+    pub fn strict_column_count(&self) -> bool {
+        self.strict_column_count
+    }
+
+    /// The number of fields of each row, if known in advance.
+    pub fn column_count(&self) -> Option<usize> {
+        self.column_count
+    }
+
+    /// The character that ends each row, or `None` if it is detected from the document.
+    pub fn record_terminator(&self) -> Option<u8> {
+        self.record_terminator
+    }
+
+    pub fn bool_style(&self) -> Option<BoolStyle> {
+        self.bool_style
+    }
+
+    pub fn quoted_as_string(&self) -> bool {
+        self.quoted_as_string
+    }
+
+    pub fn quote_anywhere(&self) -> bool {
+        self.quote_anywhere
+    }
+
+    pub fn strict_numeric(&self) -> bool {
+        self.strict_numeric
+    }
+
+    pub fn trim_chars(&self) -> Option<&[u8]> {
+        self.trim_chars.as_deref()
+    }
+
+    pub fn ascii_trim(&self) -> bool {
+        self.ascii_trim
+    }
+
+    pub fn has_header(&self) -> bool {
+        self.header_rows > 0
+    }
+
+    /// The number of rows of the header, 0 if the document has no header.
+    pub fn header_rows(&self) -> usize {
+        self.header_rows
+    }
+
+    pub fn header_separator(&self) -> &str {
+        &self.header_separator
+    }
+
+    pub fn escape(&self) -> Option<u8> {
+        self.escape
+    }
+
+    /// The delimiter of the header rows, or `None` if it is the [ParseContext::delimiter].
+    pub fn header_delimiter(&self) -> Option<u8> {
+        self.header_delimiter
+    }
+
+    pub fn comment(&self) -> Option<u8> {
+        self.comment
+    }
+
+    pub fn comment_mode(&self) -> CommentMode {
+        self.comment_mode
+    }
+
+    pub fn skip_empty_rows(&self) -> bool {
+        self.skip_empty_rows
+    }
+
+    pub fn skip_delimiter_only_rows(&self) -> bool {
+        self.skip_delimiter_only_rows
+    }
+
+    pub fn trailing_delimiter(&self) -> TrailingDelimiter {
+        self.trailing_delimiter
+    }
+
+    /// Returns true if the row is skipped, as an empty row or a row of only delimiters.
+    pub(crate) fn is_skipped_row(&self, row: &[u8]) -> bool {
+        let blank = |c: &u8| c.is_ascii_whitespace();
+        (self.skip_empty_rows && row.iter().all(blank))
+            || (self.skip_delimiter_only_rows
+                && row.iter().all(|c| blank(c) || *c == self.delimiter))
+    }
+
+    pub fn type_inference(&self) -> &TypeInference {
+        &self.type_inference
+    }
+
+    pub fn integers_as_float(&self) -> bool {
+        self.integers_as_float
+    }
+
+    pub fn empty_as_null(&self) -> bool {
+        self.empty_as_null
+    }
+
+    /// The types tried in order to infer fields, without [ColumnType::Int] if integers
+    /// are read as floats.
+    pub(crate) fn inferred_types(&self) -> impl Iterator<Item = &ColumnType> {
+        self.type_inference
+            .order()
+            .iter()
+            .filter(|t| !(self.integers_as_float && **t == ColumnType::Int))
+    }
+
+    /// Returns the text of the line without its comment character and the whitespace
+    /// around it, if the line is a comment: its first non-whitespace character is the
+    /// comment character.
+    pub(crate) fn comment_text<'a>(&self, line: &'a [u8]) -> Option<&'a [u8]> {
+        let comment = self.comment?;
+        match line.trim_ascii_start().split_first() {
+            Some((first, text)) if *first == comment => Some(text.trim_ascii_start()),
+            _ => None,
+        }
+    }
+
+    /// Returns the character that ends each row of the document: the configured one, or
+    /// `\n` unless the document only contains `\r`, as in files written by classic Mac OS.
+    pub(crate) fn detect_record_terminator(&self, data: &[u8]) -> u8 {
+        if let Some(terminator) = self.record_terminator {
+            return terminator;
+        }
+
+        if memchr::memchr(NEWLINE, data).is_none() && memchr::memchr(b'\r', data).is_some() {
+            b'\r'
+        } else {
+            NEWLINE
+        }
+    }
+
+    /// Returns true if the field has no value: either it is empty, or it only
+    /// contains whitespace and [ParseContext::whitespace_as_empty] is enabled.
+    pub(crate) fn is_empty_field(&self, span: &[u8]) -> bool {
+        span.is_empty() || (self.whitespace_as_empty && span.iter().all(u8::is_ascii_whitespace))
+    }
+
+    /// Removes the whitespace from both ends of the value: only ASCII whitespace if
+    /// [ParseContext::ascii_trim] is enabled, otherwise all Unicode whitespace.
+    pub(crate) fn trim_whitespace<'a>(&self, s: &'a str) -> &'a str {
+        if self.ascii_trim {
+            s.trim_matches(|c: char| c.is_ascii_whitespace())
+        } else {
+            s.trim()
+        }
+    }
+
+    /// Removes the trim characters, if any, from both ends of the field.
+    pub(crate) fn trim_field<'a>(&self, span: &'a [u8]) -> &'a [u8] {
+        let Some(chars) = self.trim_chars.as_deref() else {
+            return span;
+        };
+
+        let start = span.iter().take_while(|c| chars.contains(c)).count();
+        let end = span.len()
+            - span[start..]
+                .iter()
+                .rev()
+                .take_while(|c| chars.contains(c))
+                .count();
+        &span[start..end]
+    }
+
+    /// Returns the start of the field that follows a delimiter at `offset`,
+    /// skipping the initial spaces if [ParseContext::skip_initial_space] is enabled.
+    pub(crate) fn field_start(&self, row: &[u8], offset: usize) -> usize {
+        if !self.skip_initial_space {
+            return offset;
+        }
+
+        let spaces = row[offset..].iter().take_while(|b| **b == b' ').count();
+        offset + spaces
+    }
+
+    /// Returns the context to parse the document with: the delimiter declared by a
+    /// leading `sep=` line, if any, replaces the configured one. Returns an error if the
+    /// declared delimiter conflicts with the other settings, such as `sep="`.
+    pub(crate) fn try_for_document(
+        &self,
+        data: &[u8],
+    ) -> Result<Cow<'_, ParseContext>, ConfigError> {
+        match sep_line(data) {
+            Some(delimiter) if delimiter != self.delimiter => {
+                let mut context = self.clone();
+                context.delimiter = delimiter;
+                context.validate()?;
+                Ok(Cow::Owned(context))
+            }
+            _ => Ok(Cow::Borrowed(self)),
+        }
+    }
+
+    /// Like [ParseContext::try_for_document], but an invalid declared delimiter is ignored
+    /// and the configured context is used, for the callers that cannot return an error.
+    pub(crate) fn for_document(&self, data: &[u8]) -> Cow<'_, ParseContext> {
+        self.try_for_document(data).unwrap_or(Cow::Borrowed(self))
+    }
+
+    /// Returns the context to split the header rows with, that uses the header delimiter
+    /// if any.
+    pub(crate) fn for_header(&self) -> Cow<'_, ParseContext> {
+        match self.header_delimiter {
+            Some(delimiter) if delimiter != self.delimiter => {
+                let mut context = self.clone();
+                context.delimiter = delimiter;
+                Cow::Owned(context)
+            }
+            _ => Cow::Borrowed(self),
+        }
+    }
+
+    /// Checks that the settings are consistent with each other.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.validate_delimiter(self.delimiter)?;
+
+        if self.number_format.decimal_separator == self.delimiter {
+            return Err(ConfigError::DecimalSeparatorIsDelimiter(self.delimiter));
+        }
+
+        if self.number_format.grouping_separator == Some(self.number_format.decimal_separator) {
+            return Err(ConfigError::GroupingSeparatorIsDecimalSeparator(
+                self.number_format.decimal_separator,
+            ));
+        }
+
+        if let Some(delimiter) = self.header_delimiter {
+            self.validate_delimiter(delimiter)?;
+        }
+
+        if self.column_count == Some(0) {
+            return Err(ConfigError::ZeroColumnCount);
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the delimiter, of the rows or of the header, is not a character that
+    /// has another meaning.
+    fn validate_delimiter(&self, delimiter: u8) -> Result<(), ConfigError> {
+        // A carriage return ends the rows of CR-only documents, and is trimmed from CRLF rows.
+        if delimiter == NEWLINE || delimiter == b'\r' {
+            return Err(ConfigError::DelimiterIsNewline);
+        }
+
+        if self.quote == Some(delimiter) {
+            return Err(ConfigError::QuoteIsDelimiter(delimiter));
+        }
+
+        if self.escape == Some(delimiter) {
+            return Err(ConfigError::EscapeIsDelimiter(delimiter));
+        }
+
+        if self.comment == Some(delimiter) {
+            return Err(ConfigError::CommentIsDelimiter(delimiter));
+        }
+
+        if self.record_terminator == Some(delimiter) {
+            return Err(ConfigError::RecordTerminatorIsDelimiter(delimiter));
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the delimiter declared by the first line of the document, if it is a `sep=X`
+/// line, as written by Excel.
+pub(crate) fn sep_line(data: &[u8]) -> Option<u8> {
+    match data {
+        [b's', b'e', b'p', b'=', delimiter, rest @ ..]
+            if matches!(rest.first(), None | Some(b'\n') | Some(b'\r')) =>
+        {
+            Some(*delimiter)
+        }
+        _ => None,
+    }
+}
+
+/// Builds a validated [ParseContext].
+#[derive(Default)]
+pub struct ParseContextBuilder {
+    context: ParseContext,
+}
+
+impl ParseContextBuilder {
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.context.delimiter = delimiter;
+        self
+    }
+
+    /// Sets the decimal separator of floating point values.
+    ///
+    /// Note that the decimal separator cannot be the same as the delimiter, so that
+    /// a decimal comma (`,`) implies a different delimiter, typically `;`.
+    pub fn decimal_separator(mut self, separator: u8) -> Self {
+        self.context.number_format.decimal_separator = separator;
+        self
+    }
+
+    /// Sets the separator of groups of thousands in numbers, such as `,` in `1,000`.
+    /// Note that when it is the same as the delimiter, such numbers must be quoted.
+    pub fn grouping_separator(mut self, separator: Option<u8>) -> Self {
+        self.context.number_format.grouping_separator = separator;
+        self
+    }
+
+    /// Skips the spaces that immediately follow a delimiter, so that `a, b` is
+    /// split into `a` and `b` rather than `a` and ` b`.
+    pub fn skip_initial_space(mut self, skip: bool) -> Self {
+        self.context.skip_initial_space = skip;
+        self
+    }
+
+    /// Sets the character that surrounds quoted fields, or disables quoting with `None`.
+    /// Defaults to `"`.
+    pub fn quote(mut self, quote: Option<u8>) -> Self {
+        self.context.quote = quote;
+        self
+    }
+
+    /// Treats fields that only contain whitespace as empty fields.
+    pub fn whitespace_as_empty(mut self, enabled: bool) -> Self {
+        self.context.whitespace_as_empty = enabled;
+        self
+    }
+
+    /// Removes the `\r` at the end of each row, so that files mixing `\n` and `\r\n`
+    /// line endings are read consistently. Enabled by default.
+    pub fn trim_cr(mut self, enabled: bool) -> Self {
+        self.context.trim_cr = enabled;
+        self
+    }
+
+    /// Rejects rows with data between the closing quote of a field and the next
+    /// delimiter, such as `"quoted"garbage`. By default, this data is appended to the value.
+    pub fn strict_quotes(mut self, strict: bool) -> Self {
+        self.context.strict_quotes = strict;
+        self
+    }
+
+    /// Rejects rows with more fields than a schema generated by [crate::schema]. By
+    /// default, the extra fields are ignored.
+    pub fn strict_column_count(mut self, strict: bool) -> Self {
+        self.context.strict_column_count = strict;
+        self
+    }
+
+    /// Sets the number of fields of each row, when it is known in advance, so that the
+    /// fields of a row are allocated at once. With [ParseContextBuilder::strict_column_count],
+    /// rows with another number of fields are rejected. The count cannot be 0.
+    pub fn column_count(mut self, count: Option<usize>) -> Self {
+        self.context.column_count = count;
+        self
+    }
+
+    /// Sets the character that ends each row, such as `\r` for files written by
+    /// classic Mac OS. By default, it is detected from the document.
+    pub fn record_terminator(mut self, terminator: Option<u8>) -> Self {
+        self.context.record_terminator = terminator;
+        self
+    }
+
+    /// Sets the labels of boolean values. By default, only `true` and `false` are accepted,
+    /// with this exact case.
+    pub fn bool_style(mut self, style: BoolStyle) -> Self {
+        self.context.bool_style = Some(style);
+        self
+    }
+
+    /// Infers quoted fields as strings, even if they look numeric, such as `"01234"`.
+    /// This only applies to the [crate::DefaultSchema]. See [crate::QuoteStyle] to write
+    /// such fields.
+    pub fn quoted_as_string(mut self, enabled: bool) -> Self {
+        self.context.quoted_as_string = enabled;
+        self
+    }
+
+    /// Makes quotes special anywhere in a field, not only at its start, to read dirty data:
+    /// quotes are removed and the delimiters between them are part of the value, so that
+    /// `abc"d,e"f` is the single field `abcd,ef`. By default, such quotes are literal.
+    pub fn quote_anywhere(mut self, enabled: bool) -> Self {
+        self.context.quote_anywhere = enabled;
+        self
+    }
+
+    /// Rejects the numbers that don't follow the exact grammar of the [NumberFormat], such
+    /// as `1,,234`, `1.2.3`, `--5`, `5.` or `inf`, with [super::ParseError::MalformedNumber].
+    /// By default, numbers are parsed leniently.
+    pub fn strict_numeric(mut self, strict: bool) -> Self {
+        self.context.strict_numeric = strict;
+        self
+    }
+
+    /// Sets the characters removed from both ends of fields, such as `*` to read `*value*`
+    /// as `value`. String fields are only trimmed when this is set, and numbers are also
+    /// trimmed of whitespace. [crate::CsvReader::read_raw_strings] trims whitespace
+    /// when this is not set.
+    pub fn trim_chars(mut self, chars: Option<&[u8]>) -> Self {
+        self.context.trim_chars = chars.map(<[u8]>::to_vec);
+        self
+    }
+
+    /// Only trims ASCII whitespace, such as spaces and tabs, so that a non-breaking space
+    /// `\u{a0}` is kept. By default, all Unicode whitespace is trimmed.
+    ///
+    /// This applies to the parsers that receive the context: numbers, booleans, the
+    /// [super::CurrencyParser], the keys of a [super::LookupParser], the dates of a
+    /// `MultiFormatDateParser`, the labels of a [crate::csv_enum], and
+    /// [crate::CsvReader::read_raw_strings]. The parsers without a context, such as the
+    /// [super::CoordParser] and the UUIDs, always trim all Unicode whitespace.
+    pub fn ascii_trim(mut self, enabled: bool) -> Self {
+        self.context.ascii_trim = enabled;
+        self
+    }
+
+    /// Whether the first row of the document is a header. Otherwise, it is read as data,
+    /// and the document has no column names. Enabled by default.
+    pub fn has_header(mut self, enabled: bool) -> Self {
+        self.context.header_rows = usize::from(enabled);
+        self
+    }
+
+    /// Sets the number of rows of the header, such as 2 for a header with a row of groups
+    /// above a row of columns. The names of the rows are joined with the header separator.
+    /// Defaults to 1.
+    pub fn header_rows(mut self, rows: usize) -> Self {
+        self.context.header_rows = rows;
+        self
+    }
+
+    /// Sets the separator between the names of a header of several rows, such as
+    /// `Sales / Q1`. Defaults to ` / `.
+    pub fn header_separator(mut self, separator: &str) -> Self {
+        self.context.header_separator = separator.to_string();
+        self
+    }
+
+    /// Sets the character that escapes the next character in unquoted fields, such as `\`
+    /// in `a\,b`. Disabled by default. See [crate::parser::RowSpanIterator].
+    pub fn escape(mut self, escape: Option<u8>) -> Self {
+        self.context.escape = escape;
+        self
+    }
+
+    /// Sets a delimiter for the header rows that differs from the delimiter of the data
+    /// rows, such as a tab-delimited header over comma-delimited data. By default, the
+    /// header rows use the [ParseContextBuilder::delimiter].
+    pub fn header_delimiter(mut self, delimiter: Option<u8>) -> Self {
+        self.context.header_delimiter = delimiter;
+        self
+    }
+
+    /// Sets the character that starts comment lines, such as `#`. Comment lines are not
+    /// rows, whatever the [CommentMode]. Disabled by default.
+    pub fn comment(mut self, comment: Option<u8>) -> Self {
+        self.context.comment = comment;
+        self
+    }
+
+    pub fn comment_mode(mut self, mode: CommentMode) -> Self {
+        self.context.comment_mode = mode;
+        self
+    }
+
+    /// Skips the empty rows and the rows of only whitespace, so that blank lines are not
+    /// read as records. Disabled by default. A row of only delimiters, such as `,,,`, is
+    /// still a record, see [ParseContextBuilder::skip_delimiter_only_rows].
+    pub fn skip_empty_rows(mut self, skip: bool) -> Self {
+        self.context.skip_empty_rows = skip;
+        self
+    }
+
+    /// Skips the rows of only delimiters and whitespace, such as `,,,`, as well as the
+    /// empty rows. Disabled by default.
+    pub fn skip_delimiter_only_rows(mut self, skip: bool) -> Self {
+        self.context.skip_delimiter_only_rows = skip;
+        self
+    }
+
+    /// Sets whether a delimiter at the end of a row is followed by an empty field. By
+    /// default it is, see [TrailingDelimiter].
+    pub fn trailing_delimiter(mut self, policy: TrailingDelimiter) -> Self {
+        self.context.trailing_delimiter = policy;
+        self
+    }
+
+    /// Sets the types tried in order to infer the fields of a
+    /// [crate::DefaultSchema], see [TypeInference].
+    pub fn type_inference(mut self, inference: TypeInference) -> Self {
+        self.context.type_inference = inference;
+        self
+    }
+
+    /// Infers integers such as `1` as [super::FieldValue::Float] instead of
+    /// [super::FieldValue::Int].
+    pub fn integers_as_float(mut self, enabled: bool) -> Self {
+        self.context.integers_as_float = enabled;
+        self
+    }
+
+    /// Reads the unquoted empty fields of typed schemas as `None`, even for `String`
+    /// fields, while a quoted empty field (`""`) is an empty string. Enabled by default.
+    /// Otherwise, every empty field is parsed by the field parser.
+    pub fn empty_as_null(mut self, enabled: bool) -> Self {
+        self.context.empty_as_null = enabled;
+        self
+    }
+
+    pub fn build(self) -> Result<ParseContext, ConfigError> {
+        self.context.validate()?;
+        Ok(self.context)
+    }
+}
+
+/// An invalid combination of settings in a [ParseContext].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigError {
+    /// The delimiter is a newline or a carriage return, which end the rows.
+    DelimiterIsNewline,
+    /// The decimal separator is the same character as the delimiter.
+    DecimalSeparatorIsDelimiter(u8),
+    /// The grouping separator is the same character as the decimal separator.
+    GroupingSeparatorIsDecimalSeparator(u8),
+    /// The quote is the same character as the delimiter.
+    QuoteIsDelimiter(u8),
+    /// The escape character is the same character as the delimiter.
+    EscapeIsDelimiter(u8),
+    /// The comment character is the same character as the delimiter.
+    CommentIsDelimiter(u8),
+    /// The record terminator is the same character as the delimiter.
+    RecordTerminatorIsDelimiter(u8),
+    /// The column range of a fixed-width row ends before it starts.
+    InvalidColumnRange(std::ops::Range<usize>),
+    /// The number of fields of each row is 0.
+    ZeroColumnCount,
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::DelimiterIsNewline => {
+                write!(f, "the delimiter cannot be a newline or a carriage return")
+            }
+            ConfigError::DecimalSeparatorIsDelimiter(c) => write!(
+                f,
+                "the decimal separator '{}' cannot be the same as the delimiter",
+                *c as char
+            ),
+            ConfigError::GroupingSeparatorIsDecimalSeparator(c) => write!(
+                f,
+                "the grouping separator '{}' cannot be the same as the decimal separator",
+                *c as char
+            ),
+            ConfigError::QuoteIsDelimiter(c) => write!(
+                f,
+                "the quote '{}' cannot be the same as the delimiter",
+                *c as char
+            ),
+            ConfigError::EscapeIsDelimiter(c) => write!(
+                f,
+                "the escape character '{}' cannot be the same as the delimiter",
+                *c as char
+            ),
+            ConfigError::CommentIsDelimiter(c) => write!(
+                f,
+                "the comment character '{}' cannot be the same as the delimiter",
+                *c as char
+            ),
+            ConfigError::RecordTerminatorIsDelimiter(c) => write!(
+                f,
+                "the record terminator '{}' cannot be the same as the delimiter",
+                c.escape_ascii()
+            ),
+            ConfigError::InvalidColumnRange(range) => {
+                write!(f, "the column range {:?} ends before it starts", range)
+            }
+            ConfigError::ZeroColumnCount => write!(f, "the column count cannot be 0"),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[cfg(test)]
+mod test {
+    mod number_format {
+        use crate::parser::NumberFormat;
+
+        #[test]
+        fn normalize() {
+            let format = NumberFormat {
+                decimal_separator: b',',
+                grouping_separator: Some(b'.'),
+            };
+
+            assert_eq!("1234.5", format.normalize("1.234,5"));
+            assert_eq!("12", NumberFormat::default().normalize("12"));
+        }
+
+        #[test]
+        fn is_well_formed() {
+            let format = NumberFormat {
+                decimal_separator: b'.',
+                grouping_separator: Some(b','),
+            };
+
+            for valid in [
+                "0",
+                "-5",
+                "+1.25",
+                "1,234",
+                "12,345,678.9",
+                "1e10",
+                "-2.5E-3",
+            ] {
+                assert!(format.is_well_formed(valid), "{}", valid);
+            }
+            for malformed in [
+                "", "-", "1,,234", "1.2.3", "--5", "5.", ".5", "1,234,", "12,34", "1,2345",
+                "1.5,000", "1e", "1e+", "1e5.5", "inf", "NaN", "0x10", " 5",
+            ] {
+                assert!(!format.is_well_formed(malformed), "{}", malformed);
+            }
+        }
+    }
+
+    mod sep_line {
+        use crate::parser::{context::sep_line, ConfigError, ParseContext};
+
+        #[test]
+        fn detects_declared_delimiter() {
+            assert_eq!(Some(b';'), sep_line(b"sep=;\nname;age\n"));
+            assert_eq!(Some(b'\t'), sep_line(b"sep=\t\r\nname\tage\r\n"));
+            assert_eq!(None, sep_line(b"sep=;;\nname\n"));
+            assert_eq!(None, sep_line(b"name,age\n"));
+        }
+
+        #[test]
+        fn for_document_uses_declared_delimiter() {
+            let context = ParseContext::default();
+
+            assert_eq!(b';', context.for_document(b"sep=;\n").delimiter());
+            assert_eq!(b',', context.for_document(b"a;b\n").delimiter());
+        }
+
+        #[test]
+        fn invalid_declared_delimiter_returns_err() {
+            let context = ParseContext::default();
+
+            assert_eq!(
+                ConfigError::QuoteIsDelimiter(b'"'),
+                context.try_for_document(b"sep=\"\na\n").unwrap_err()
+            );
+            assert_eq!(
+                ConfigError::DelimiterIsNewline,
+                context.try_for_document(b"sep=\n\na\n").unwrap_err()
+            );
+            assert_eq!(
+                ConfigError::DecimalSeparatorIsDelimiter(b'.'),
+                context.try_for_document(b"sep=.\na\n").unwrap_err()
+            );
+            assert_eq!(b',', context.for_document(b"sep=\"\na\n").delimiter());
+        }
+    }
+
+    mod builder {
+        use crate::{
+            parser::{ConfigError, ParseContext},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn default_is_valid() {
+            assert!(ParseContext::builder().build().is_ok());
+        }
+
+        #[test]
+        fn decimal_comma_with_semicolon_delimiter_is_valid() {
+            let context = ParseContext::builder()
+                .delimiter(b';')
+                .decimal_separator(b',')
+                .build()
+                .unwrap();
+
+            assert_eq!(b';', context.delimiter());
+            assert_eq!(b',', context.number_format().decimal_separator);
+        }
+
+        #[test]
+        fn decimal_separator_equal_to_delimiter_returns_err() {
+            let result = ParseContext::builder().decimal_separator(b',').build();
+
+            assert_eq!(
+                ConfigError::DecimalSeparatorIsDelimiter(b','),
+                result.unwrap_err()
+            );
+        }
+
+        #[test]
+        fn grouping_separator_equal_to_decimal_separator_returns_err() {
+            let result = ParseContext::builder()
+                .grouping_separator(Some(b'.'))
+                .build();
+
+            assert_eq!(
+                ConfigError::GroupingSeparatorIsDecimalSeparator(b'.'),
+                result.unwrap_err()
+            );
+        }
+
+        #[test]
+        fn quote_equal_to_delimiter_returns_err() {
+            let result = ParseContext::builder().quote(Some(b',')).build();
+
+            assert_eq!(ConfigError::QuoteIsDelimiter(b','), result.unwrap_err());
+        }
+
+        #[test]
+        fn escape_equal_to_delimiter_returns_err() {
+            let result = ParseContext::builder().escape(Some(b',')).build();
+
+            assert_eq!(ConfigError::EscapeIsDelimiter(b','), result.unwrap_err());
+        }
+
+        #[test]
+        fn record_terminator_equal_to_delimiter_returns_err() {
+            let result = ParseContext::builder()
+                .delimiter(b'\t')
+                .record_terminator(Some(b'\t'))
+                .build();
+
+            assert_eq!(
+                ConfigError::RecordTerminatorIsDelimiter(b'\t'),
+                result.unwrap_err()
+            );
+        }
+
+        #[test]
+        fn zero_column_count_returns_err() {
+            let result = ParseContext::builder().column_count(Some(0)).build();
+
+            assert_eq!(ConfigError::ZeroColumnCount, result.unwrap_err());
+            assert!(CsvReader::<DefaultSchema>::default()
+                .with_column_count(0)
+                .is_err());
+        }
+
+        #[test]
+        fn header_delimiter_conflicts_return_err() {
+            let quote = ParseContext::builder().header_delimiter(Some(b'"')).build();
+            assert_eq!(ConfigError::QuoteIsDelimiter(b'"'), quote.unwrap_err());
+
+            let terminator = ParseContext::builder()
+                .record_terminator(Some(0x1e))
+                .header_delimiter(Some(0x1e))
+                .build();
+            assert_eq!(
+                ConfigError::RecordTerminatorIsDelimiter(0x1e),
+                terminator.unwrap_err()
+            );
+
+            let newline = ParseContext::builder()
+                .header_delimiter(Some(b'\n'))
+                .build();
+            assert_eq!(ConfigError::DelimiterIsNewline, newline.unwrap_err());
+
+            let carriage_return = ParseContext::builder().delimiter(b'\r').build();
+            assert_eq!(
+                ConfigError::DelimiterIsNewline,
+                carriage_return.unwrap_err()
+            );
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use std::error::Error;
+
+use chrono::NaiveDate;
+
+use super::{ConfiguredFieldParser, FieldSpan, ParseContext, ParseError};
+
+/// Parses dates written in several formats, such as `2023-01-15`, `01/15/2023` and
+/// `15.01.2023` in the same column. The formats use the syntax of [chrono::format::strftime]
+/// and are tried in order: the first one that matches wins.
+///
+/// Returns [ParseError::UnknownFormat] if the value matches none of the formats.
+pub struct MultiFormatDateParser {
+    formats: Vec<String>,
+}
+
+impl MultiFormatDateParser {
+    pub fn new(formats: &[&str]) -> Self {
+        Self {
+            formats: formats.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+}
+
+impl ConfiguredFieldParser<NaiveDate> for MultiFormatDateParser {
+    fn parse_field(
+        &self,
+        span: &FieldSpan,
+        context: &ParseContext,
+    ) -> Result<NaiveDate, Box<dyn Error>> {
+        let s = context.trim_whitespace(std::str::from_utf8(span)?);
+
+        self.formats
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+            .ok_or_else(|| ParseError::UnknownFormat(s.to_string()).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use crate::{
+        parser::{ConfiguredFieldParser, MultiFormatDateParser, ParseContext, ParseError},
+        CsvReader,
+    };
+
+    crate::schema!(Event, name:String, date:NaiveDate = MultiFormatDateParser::new(&["%Y-%m-%d", "%m/%d/%Y", "%d.%m.%Y"]));
+
+    #[test]
+    fn parse_column_with_several_formats() {
+        let csv = b"name,date\na,2023-01-15\nb,01/15/2023\nc,15.01.2023\nd,Jan 15\n";
+
+        let rows = CsvReader::<Event>::default().read(csv).unwrap();
+
+        let expected = NaiveDate::from_ymd_opt(2023, 1, 15);
+        assert_eq!(expected, rows[0].date);
+        assert_eq!(expected, rows[1].date);
+        assert_eq!(expected, rows[2].date);
+        assert_eq!(None, rows[3].date);
+    }
+
+    #[test]
+    fn first_matching_format_wins() {
+        let context = ParseContext::default();
+        let parser = MultiFormatDateParser::new(&["%d/%m/%Y", "%m/%d/%Y"]);
+
+        assert_eq!(
+            NaiveDate::from_ymd_opt(2023, 2, 1).unwrap(),
+            parser.parse_field(b"01/02/2023", &context).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_unknown_format_returns_err() {
+        let context = ParseContext::default();
+        let parser = MultiFormatDateParser::new(&["%Y-%m-%d"]);
+
+        let err = parser.parse_field(b"15.01.2023", &context).unwrap_err();
+
+        assert_eq!(
+            Some(&ParseError::UnknownFormat("15.01.2023".into())),
+            err.downcast_ref::<ParseError>()
+        );
+    }
+}
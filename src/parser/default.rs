@@ -1,47 +1,545 @@
-use crate::DefaultSchema;
+use std::error::Error;
 
-use super::{FieldParser, FloatParser, ParseContext, RowParser, RowSpan, StringParser};
+use crate::{CheckedSchema, DefaultSchema};
+
+use super::{
+    check_fields, BoolParser, FieldParser, FloatParser, IntParser, ParseContext, ParseError,
+    RowParser, RowSpan, RowSpanIterator, StrictRowParser, StringParser,
+};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum FieldValue {
     Float(f64),
     String(String),
+    /// An integer without decimal separator nor exponent, such as `1` but not `1.0` or `1e0`.
+    Int(i64),
+    /// `true` or `false`, or the labels of the [super::BoolStyle] of the context.
+    Bool(bool),
+}
+
+/// The type of a [FieldValue], without its value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColumnType {
+    Float,
+    String,
+    Int,
+    Bool,
+}
+
+impl ColumnType {
+    /// The type of a column that has values of both types: integers and floats are
+    /// floats, as [TypeInference] reads them, and any other mix is strings.
+    pub(crate) fn widen(self, other: ColumnType) -> ColumnType {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (ColumnType::Int, ColumnType::Float) | (ColumnType::Float, ColumnType::Int) => {
+                ColumnType::Float
+            }
+            _ => ColumnType::String,
+        }
+    }
+
+    /// Parses the field as a value of this type.
+    pub(crate) fn parse(
+        &self,
+        span: &RowSpan,
+        context: &ParseContext,
+    ) -> Result<FieldValue, Box<dyn Error>> {
+        match self {
+            ColumnType::Float => {
+                FloatParser::<f64>::parse_with_context(span, context).map(FieldValue::Float)
+            }
+            ColumnType::String => {
+                StringParser::parse_with_context(span, context).map(FieldValue::String)
+            }
+            ColumnType::Int => {
+                IntParser::<i64>::parse_with_context(span, context).map(FieldValue::Int)
+            }
+            ColumnType::Bool => BoolParser::parse_with_context(span, context).map(FieldValue::Bool),
+        }
+    }
+}
+
+impl FieldValue {
+    pub fn column_type(&self) -> ColumnType {
+        match self {
+            FieldValue::Float(_) => ColumnType::Float,
+            FieldValue::String(_) => ColumnType::String,
+            FieldValue::Int(_) => ColumnType::Int,
+            FieldValue::Bool(_) => ColumnType::Bool,
+        }
+    }
+}
+
+impl std::fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldValue::Float(v) => write!(f, "{}", v),
+            FieldValue::String(v) => write!(f, "{}", v),
+            FieldValue::Int(v) => write!(f, "{}", v),
+            FieldValue::Bool(v) => write!(f, "{}", v),
+        }
+    }
+}
+
+/// The types tried in order to infer the value of a field by the [DefaultRowParser], the
+/// first type that can parse the field wins. A field that no type can parse is empty.
+///
+/// The default tries [ColumnType::Int], then [ColumnType::Bool], then [ColumnType::Float],
+/// then [ColumnType::String], so that `1` is an integer, `true` is a boolean, and `1.0`
+/// and `1e0` are floats. Integers can be read as floats with
+/// [super::ParseContextBuilder::integers_as_float].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeInference {
+    order: Vec<ColumnType>,
+}
+
+impl TypeInference {
+    /// Tries the types in this order, such as `[Bool, Int, Float, String]`.
+    pub fn new(order: &[ColumnType]) -> Self {
+        Self {
+            order: order.to_vec(),
+        }
+    }
+
+    pub fn order(&self) -> &[ColumnType] {
+        &self.order
+    }
+}
+
+impl Default for TypeInference {
+    fn default() -> Self {
+        Self::new(&[
+            ColumnType::Int,
+            ColumnType::Bool,
+            ColumnType::Float,
+            ColumnType::String,
+        ])
+    }
 }
 
 pub struct DefaultRowParser {}
 
 impl DefaultRowParser {
-    fn try_parse_field(span: &RowSpan) -> Option<FieldValue> {
-        if span.is_empty() {
+    pub(crate) fn try_parse_field(
+        span: &RowSpan,
+        quoted: bool,
+        context: &ParseContext,
+    ) -> Option<FieldValue> {
+        if context.is_empty_field(span) {
             None
-        } else if let Ok(float) = FloatParser::<f64>::parse(span) {
-            Some(FieldValue::Float(float))
-        } else if let Ok(v) = StringParser::parse(span) {
-            Some(FieldValue::String(v))
+        } else if quoted && context.quoted_as_string {
+            StringParser::parse_with_context(span, context)
+                .ok()
+                .map(FieldValue::String)
         } else {
-            None
+            context
+                .inferred_types()
+                .find_map(|t| t.parse(span, context).ok())
+        }
+    }
+
+    /// Parses the row like a [DefaultSchema], but into an array of `N` fields instead of
+    /// allocating them, for rows whose width is known at compile time. Missing fields
+    /// are empty.
+    ///
+    /// Returns [ParseError::WrongColumnCount] if the row has more than `N` fields.
+    pub fn parse_array<const N: usize>(
+        row: &RowSpan,
+        context: &ParseContext,
+    ) -> Result<[Option<FieldValue>; N], ParseError> {
+        Self::parse_array_fields(&mut RowSpanIterator::new(context, row), context)
+    }
+
+    /// Parses the fields of the iterator like [DefaultRowParser::parse_array], so that
+    /// the iterator can be checked afterwards, see [check_fields].
+    pub(crate) fn parse_array_fields<const N: usize>(
+        iterator: &mut RowSpanIterator,
+        context: &ParseContext,
+    ) -> Result<[Option<FieldValue>; N], ParseError> {
+        let mut fields = std::array::from_fn(|_| None);
+
+        for field in fields.iter_mut() {
+            let Some(span) = iterator.next() else {
+                return Ok(fields);
+            };
+            *field = Self::try_parse_field(&span, iterator.is_quoted(), context);
         }
+
+        match iterator.count() {
+            0 => Ok(fields),
+            extra => Err(ParseError::WrongColumnCount {
+                line: None,
+                expected: N,
+                found: N + extra,
+            }),
+        }
+    }
+
+    fn parse_fields(iterator: &mut RowSpanIterator, context: &ParseContext) -> DefaultSchema {
+        let mut fields = Vec::with_capacity(context.column_count().unwrap_or_default());
+
+        while let Some(span) = iterator.next() {
+            fields.push(Self::try_parse_field(&span, iterator.is_quoted(), context));
+        }
+
+        DefaultSchema::new(fields)
     }
 }
 
 impl RowParser<DefaultSchema> for DefaultRowParser {
     fn parse(row: &RowSpan, context: &ParseContext) -> DefaultSchema {
-        let mut fields: Vec<Option<FieldValue>> = Vec::new();
+        Self::parse_fields(&mut RowSpanIterator::new(context, row), context)
+    }
+
+    fn parse_checked(
+        row: &RowSpan,
+        line: usize,
+        context: &ParseContext,
+    ) -> Result<DefaultSchema, ParseError> {
+        let mut iterator = RowSpanIterator::new(context, row);
+        let schema = Self::parse_fields(&mut iterator, context);
+        check_fields(iterator, None, line)?;
+        Ok(schema)
+    }
+}
+
+impl StrictRowParser<DefaultSchema> for DefaultRowParser {
+    fn parse_strict(row: &RowSpan, context: &ParseContext) -> Result<DefaultSchema, ParseError> {
+        strict_fields(CheckedRowParser::parse(row, context))
+    }
+
+    fn parse_strict_checked(
+        row: &RowSpan,
+        line: usize,
+        context: &ParseContext,
+    ) -> Result<DefaultSchema, ParseError> {
+        let mut iterator = RowSpanIterator::new(context, row);
+        let schema = CheckedRowParser::parse_fields(&mut iterator, context);
+        check_fields(iterator, None, line)?;
+        strict_fields(schema)
+    }
+}
+
+/// Returns the error of the first field that cannot be parsed, if any, or the values.
+fn strict_fields(schema: CheckedSchema) -> Result<DefaultSchema, ParseError> {
+    let fields = schema.fields().iter().cloned().collect::<Result<_, _>>()?;
+
+    Ok(DefaultSchema::new(fields))
+}
+
+/// Parses rows like the [DefaultRowParser], but keeps the error of each field that can't
+/// be parsed instead of turning it into an empty field.
+pub struct CheckedRowParser {}
+
+impl CheckedRowParser {
+    fn parse_field(
+        span: &RowSpan,
+        column: usize,
+        quoted: bool,
+        context: &ParseContext,
+    ) -> Result<Option<FieldValue>, ParseError> {
+        if context.is_empty_field(span) {
+            Ok(None)
+        } else if quoted && context.quoted_as_string {
+            Self::parse_string(span, column, context)
+        } else {
+            // The error of the last type tried is kept, such as invalid UTF-8 by default.
+            let mut message = "no type to infer".to_string();
+            for column_type in context.inferred_types() {
+                match column_type.parse(span, context) {
+                    Ok(v) => return Ok(Some(v)),
+                    Err(e) => message = e.to_string(),
+                }
+            }
+            Err(ParseError::InvalidField {
+                line: None,
+                column,
+                message,
+            })
+        }
+    }
 
-        let mut start = 0;
+    fn parse_string(
+        span: &RowSpan,
+        column: usize,
+        context: &ParseContext,
+    ) -> Result<Option<FieldValue>, ParseError> {
+        match StringParser::parse_with_context(span, context) {
+            Ok(v) => Ok(Some(FieldValue::String(v))),
+            Err(e) => Err(ParseError::InvalidField {
+                line: None,
+                column,
+                message: e.to_string(),
+            }),
+        }
+    }
+}
 
-        while let Some(index) = memchr::memchr(context.delimiter, &row[start..]) {
-            let span = &row[start..(start + index)];
+impl RowParser<CheckedSchema> for CheckedRowParser {
+    fn parse(row: &RowSpan, context: &ParseContext) -> CheckedSchema {
+        Self::parse_fields(&mut RowSpanIterator::new(context, row), context)
+    }
 
-            fields.push(Self::try_parse_field(span));
+    fn parse_checked(
+        row: &RowSpan,
+        line: usize,
+        context: &ParseContext,
+    ) -> Result<CheckedSchema, ParseError> {
+        let mut iterator = RowSpanIterator::new(context, row);
+        let schema = Self::parse_fields(&mut iterator, context);
+        check_fields(iterator, None, line)?;
+        Ok(schema)
+    }
+}
+
+impl CheckedRowParser {
+    fn parse_fields(iterator: &mut RowSpanIterator, context: &ParseContext) -> CheckedSchema {
+        let mut fields = Vec::with_capacity(context.column_count().unwrap_or_default());
 
-            start += index + 1;
+        while let Some(span) = iterator.next() {
+            let column = fields.len();
+            fields.push(Self::parse_field(
+                &span,
+                column,
+                iterator.is_quoted(),
+                context,
+            ));
         }
 
-        if start < row.len() - 1 {
-            fields.push(Self::try_parse_field(&row[start..]));
+        CheckedSchema::new(fields)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    mod type_inference {
+        use crate::parser::{
+            ColumnType, DefaultRowParser, FieldValue, ParseContext, RowParser, TypeInference,
+        };
+
+        fn parse(row: &[u8], order: &[ColumnType]) -> Vec<Option<FieldValue>> {
+            let context = ParseContext::builder()
+                .type_inference(TypeInference::new(order))
+                .build()
+                .unwrap();
+
+            DefaultRowParser::parse(row, &context).fields().to_vec()
         }
 
-        DefaultSchema::new(fields)
+        #[test]
+        fn types_are_tried_in_order() {
+            use ColumnType::*;
+
+            assert_eq!(
+                vec![
+                    Some(FieldValue::Bool(true)),
+                    Some(FieldValue::Int(1)),
+                    Some(FieldValue::Float(1.5)),
+                    Some(FieldValue::String("a".into())),
+                ],
+                parse(b"true,1,1.5,a", &[Bool, Int, Float, String])
+            );
+            assert_eq!(
+                vec![
+                    Some(FieldValue::String("true".into())),
+                    Some(FieldValue::String("1".into())),
+                ],
+                parse(b"true,1", &[String, Int, Bool])
+            );
+        }
+
+        #[test]
+        fn integers_are_inferred_by_default() {
+            let context = ParseContext::default();
+
+            assert_eq!(
+                &vec![
+                    Some(FieldValue::Int(1)),
+                    Some(FieldValue::Float(1.0)),
+                    Some(FieldValue::Float(1.0)),
+                    Some(FieldValue::Int(-7)),
+                ],
+                DefaultRowParser::parse(b"1,1.0,1e0,-7", &context).fields()
+            );
+        }
+
+        #[test]
+        fn integers_and_booleans_are_inferred_by_default() {
+            let context = ParseContext::default();
+
+            assert_eq!(
+                &vec![
+                    Some(FieldValue::Int(42)),
+                    Some(FieldValue::Bool(true)),
+                    Some(FieldValue::Bool(false)),
+                    Some(FieldValue::Float(4.2)),
+                    Some(FieldValue::String("yes".into())),
+                ],
+                DefaultRowParser::parse(b"42,true,false,4.2,yes", &context).fields()
+            );
+        }
+
+        #[test]
+        fn integers_as_float() {
+            let context = ParseContext::builder()
+                .integers_as_float(true)
+                .build()
+                .unwrap();
+
+            assert_eq!(
+                &vec![
+                    Some(FieldValue::Float(1.0)),
+                    Some(FieldValue::Float(1.0)),
+                    Some(FieldValue::Float(1.0)),
+                ],
+                DefaultRowParser::parse(b"1,1.0,1e0", &context).fields()
+            );
+        }
+
+        #[test]
+        fn disabled_types_give_empty_fields() {
+            assert_eq!(
+                vec![Some(FieldValue::Float(1f64)), None],
+                parse(b"1,a", &[ColumnType::Float])
+            );
+        }
+    }
+
+    mod default_row_parser {
+        use crate::{
+            parser::{DefaultRowParser, FieldValue, ParseContext, ParseError, RowParser},
+            CsvReader, DefaultSchema,
+        };
+
+        #[test]
+        fn parse_empty_row() {
+            let context = ParseContext::default();
+
+            assert!(DefaultRowParser::parse(b"", &context).fields().is_empty());
+        }
+
+        #[test]
+        fn parse_row_of_only_a_delimiter() {
+            let context = ParseContext::builder().delimiter(b';').build().unwrap();
+
+            assert_eq!(
+                &vec![None, None],
+                DefaultRowParser::parse(b";", &context).fields()
+            );
+        }
+
+        #[test]
+        fn read_document_with_blank_lines() {
+            let rows = CsvReader::<DefaultSchema>::default()
+                .read(b"a,b\n1,2\n\n3,4\n\n")
+                .unwrap();
+
+            assert_eq!(4, rows.len());
+            assert!(rows[1].fields().is_empty());
+            assert_eq!(Some(FieldValue::Int(3)), rows[2].fields()[0]);
+        }
+
+        #[test]
+        fn parse_array() {
+            let context = ParseContext::default();
+
+            let fields = DefaultRowParser::parse_array::<3>(b"1,x,2.5", &context).unwrap();
+
+            assert_eq!(
+                [
+                    Some(FieldValue::Int(1)),
+                    Some(FieldValue::String("x".into())),
+                    Some(FieldValue::Float(2.5)),
+                ],
+                fields
+            );
+            assert_eq!(
+                [Some(FieldValue::Int(1)), None, None],
+                DefaultRowParser::parse_array::<3>(b"1", &context).unwrap()
+            );
+        }
+
+        #[test]
+        fn parse_array_with_too_many_fields_returns_err() {
+            let result = DefaultRowParser::parse_array::<3>(b"1,2,3,4,5", &ParseContext::default());
+
+            assert_eq!(
+                Err(ParseError::WrongColumnCount {
+                    line: None,
+                    expected: 3,
+                    found: 5
+                }),
+                result
+            );
+        }
+
+        #[test]
+        fn read_arrays() {
+            let reader = CsvReader::<DefaultSchema>::default();
+
+            let rows = reader.read_arrays::<2>(b"a,b\n1,x\n2\n").unwrap();
+            assert_eq!(
+                vec![
+                    [
+                        Some(FieldValue::Int(1)),
+                        Some(FieldValue::String("x".into()))
+                    ],
+                    [Some(FieldValue::Int(2)), None],
+                ],
+                rows
+            );
+
+            let err = reader.read_arrays::<2>(b"a,b\n1,2\n3,4,5\n").unwrap_err();
+            assert_eq!(
+                Some(&ParseError::WrongColumnCount {
+                    line: Some(3),
+                    expected: 2,
+                    found: 3
+                }),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn read_with_column_count() {
+            let rows = CsvReader::<DefaultSchema>::default()
+                .with_column_count(3)
+                .unwrap()
+                .read(b"a,b,c\n1,x,2.5\n")
+                .unwrap();
+
+            assert_eq!(
+                &vec![
+                    Some(FieldValue::Int(1)),
+                    Some(FieldValue::String("x".into())),
+                    Some(FieldValue::Float(2.5)),
+                ],
+                rows[0].fields()
+            );
+        }
+
+        #[test]
+        fn strict_column_count_rejects_other_widths() {
+            let context = ParseContext::builder()
+                .strict_column_count(true)
+                .column_count(Some(3))
+                .build()
+                .unwrap();
+            let reader = CsvReader::<DefaultSchema>::default().with_context(context);
+
+            for (csv, found) in [(&b"a,b,c\n1,2\n"[..], 2), (b"a,b,c\n1,2,3,4\n", 4)] {
+                let err = reader.read(csv).unwrap_err();
+
+                assert_eq!(
+                    Some(&ParseError::WrongColumnCount {
+                        line: Some(2),
+                        expected: 3,
+                        found
+                    }),
+                    err.downcast_ref::<ParseError>()
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,123 @@
+use std::ops::Range;
+
+use crate::DefaultSchema;
+
+use super::{ConfigError, DefaultRowParser, FieldSpan, IntoFieldParser, ParseContext, RowSpan};
+
+/// Parses rows made of fixed-width columns rather than delimited fields.
+///
+/// Each column is a range of bytes in the row. The padding spaces around the
+/// values are removed, and the values are parsed with the usual [super::FieldParser]s.
+pub struct FixedWidthParser {
+    columns: Vec<Range<usize>>,
+}
+
+impl FixedWidthParser {
+    /// Returns an error if a column range ends before it starts.
+    pub fn new(columns: Vec<Range<usize>>) -> Result<Self, ConfigError> {
+        if let Some(range) = columns.iter().find(|range| range.start > range.end) {
+            return Err(ConfigError::InvalidColumnRange(range.clone()));
+        }
+
+        Ok(Self { columns })
+    }
+
+    pub fn columns(&self) -> &[Range<usize>] {
+        &self.columns
+    }
+
+    /// Returns the field of the column in the row. Bytes of the range that are past
+    /// the end of the row are ignored, so that a short row has empty trailing fields.
+    pub fn field<'a>(&self, row: &'a RowSpan, column: usize) -> &'a FieldSpan {
+        let range = &self.columns[column];
+        let start = range.start.min(row.len());
+        let end = range.end.min(row.len());
+
+        row[start..end].trim_ascii()
+    }
+
+    /// Returns the fields of each column in the row.
+    pub fn fields<'a>(&'a self, row: &'a RowSpan) -> impl Iterator<Item = &'a FieldSpan> + 'a {
+        (0..self.columns.len()).map(move |column| self.field(row, column))
+    }
+
+    /// Parses the field of the column with the [super::FieldParser] of `T`.
+    pub fn parse_field<T: IntoFieldParser<T>>(
+        &self,
+        row: &RowSpan,
+        column: usize,
+        context: &ParseContext,
+    ) -> Option<T> {
+        super::try_parse_with_context(self.field(row, column), context)
+    }
+
+    /// Parses the row, inferring the type of each field like the [DefaultRowParser].
+    pub fn parse_row(&self, row: &RowSpan, context: &ParseContext) -> DefaultSchema {
+        let fields = self
+            .fields(row)
+            .map(|span| DefaultRowParser::try_parse_field(span, false, context))
+            .collect();
+
+        DefaultSchema::new(fields)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::{ConfigError, FieldValue, FixedWidthParser, ParseContext};
+
+    #[test]
+    fn parse_typed_fields() {
+        let parser = FixedWidthParser::new(vec![0..8, 8..14]).unwrap();
+        let context = ParseContext::default();
+        let row = b"alice    12.5";
+
+        assert_eq!(
+            Some("alice".to_string()),
+            parser.parse_field::<String>(row, 0, &context)
+        );
+        assert_eq!(Some(12.5f64), parser.parse_field::<f64>(row, 1, &context));
+    }
+
+    #[test]
+    fn parse_row_infers_types() {
+        let parser = FixedWidthParser::new(vec![0..8, 8..14]).unwrap();
+        let context = ParseContext::default();
+
+        let row = parser.parse_row(b"bob        3", &context);
+
+        assert_eq!(
+            &vec![
+                Some(FieldValue::String("bob".into())),
+                Some(FieldValue::Int(3))
+            ],
+            row.fields()
+        );
+    }
+
+    #[test]
+    fn short_row_has_empty_fields() {
+        let parser = FixedWidthParser::new(vec![0..4, 4..8]).unwrap();
+
+        let fields: Vec<_> = parser.fields(b"ab").collect();
+
+        assert_eq!(fields, vec![&b"ab"[..], &b""[..]]);
+    }
+
+    #[test]
+    fn range_past_the_end_of_a_short_row() {
+        let parser = FixedWidthParser::new(vec![0..2, 6..10]).unwrap();
+        let context = ParseContext::default();
+
+        assert_eq!(Some(&b""[..]), parser.fields(b"ab cd").nth(1));
+        assert_eq!(None, parser.parse_field::<i32>(b"ab cd", 1, &context));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn reversed_range_returns_err() {
+        let result = FixedWidthParser::new(vec![0..4, 8..4]);
+
+        assert_eq!(ConfigError::InvalidColumnRange(8..4), result.err().unwrap());
+    }
+}
@@ -0,0 +1,75 @@
+use std::{error::Error, marker::PhantomData};
+
+use serde::de::DeserializeOwned;
+
+use super::{FieldParser, RowSpan};
+
+/// Parses a field containing JSON, such as `{"k":1}`, into `T`.
+///
+/// Note that in a CSV document, such a field is typically quoted (`"{""k"":1}"`).
+/// The quotes are removed before parsing the JSON.
+pub struct JsonParser<T: DeserializeOwned> {
+    marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> JsonParser<T> {
+    pub fn new() -> Self {
+        Self {
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Default for JsonParser<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: DeserializeOwned> FieldParser<T> for JsonParser<T> {
+    fn parse(span: &RowSpan) -> Result<T, Box<dyn Error>> {
+        match serde_json::from_slice(span) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde::Deserialize;
+
+    use crate::{
+        parser::{FieldParser, JsonParser},
+        CsvReader,
+    };
+
+    #[derive(Debug, PartialEq, Deserialize, Default, Clone)]
+    struct Meta {
+        k: i64,
+    }
+
+    crate::schema!(Row, name:String, meta:Meta = JsonParser::<Meta>::new());
+
+    #[test]
+    fn parse_valid_json_returns_ok() {
+        assert_eq!(
+            Meta { k: 1 },
+            JsonParser::<Meta>::parse(b"{\"k\":1}").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_json_returns_err() {
+        assert!(JsonParser::<Meta>::parse(b"{k:1}").is_err());
+    }
+
+    #[test]
+    fn parse_quoted_json_cell() {
+        let csv = b"name,meta\nfoo,\"{\"\"k\"\":1}\"\n";
+
+        let rows = CsvReader::<Row>::default().read(csv).unwrap();
+
+        assert_eq!(Some(Meta { k: 1 }), rows[0].meta);
+    }
+}
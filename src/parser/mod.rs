@@ -1,42 +1,254 @@
+#[cfg(feature = "base64")]
+pub mod base64;
+pub mod borrowed;
+pub mod context;
+#[cfg(feature = "chrono")]
+pub mod date;
 pub mod default;
+pub mod fixed_width;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod tuple;
+#[cfg(feature = "uuid")]
+pub mod uuid;
 
-use std::{error::Error, marker::PhantomData};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error::Error,
+    marker::PhantomData,
+    num::{
+        NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8,
+    },
+    str::FromStr,
+};
 
-pub use default::{DefaultRowParser, FieldValue};
+#[cfg(feature = "base64")]
+pub use base64::Base64Parser;
+pub use borrowed::{
+    borrow_field, try_parse_borrowed, BorrowedFieldParser, BorrowedRowParser, CowStrParser,
+    IntoBorrowedFieldParser, IntoBorrowedRowParser, StrParser,
+};
+pub use context::{
+    BoolStyle, CommentMode, ConfigError, NumberFormat, ParseContext, ParseContextBuilder,
+    TrailingDelimiter,
+};
+#[cfg(feature = "chrono")]
+pub use date::MultiFormatDateParser;
+pub use default::{CheckedRowParser, ColumnType, DefaultRowParser, FieldValue, TypeInference};
 use fast_float::FastFloat;
+pub use fixed_width::FixedWidthParser;
+#[cfg(feature = "serde")]
+pub use json::JsonParser;
+pub use tuple::TupleRow;
+#[cfg(feature = "uuid")]
+pub use uuid::UuidParser;
 
-use crate::{DefaultSchema, COMMA};
+use crate::{CheckedSchema, DefaultSchema};
 
 pub type RowSpan = [u8];
 pub type FieldSpan = [u8];
 
-pub enum ParseError {}
-
-pub struct ParseContext {
-    delimiter: u8,
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The value does not match any of the expected labels, such as the labels of an enum
+    /// generated by [crate::csv_enum] or of a [BoolStyle].
+    UnknownLabel(String),
+    /// The header has no column with this name.
+    UnknownColumn(String),
+    /// The header row is not the expected one.
+    UnexpectedHeader(String),
+    /// The value of an integer field has a fractional part.
+    FractionalInteger(String),
+    /// A quoted field of the line (1-based) has data after its closing quote.
+    TrailingAfterQuote { line: usize },
+    /// Reading was cancelled before the line (1-based), see [crate::CsvReader::read_cancellable].
+    Cancelled { line: usize },
+    /// The value matches none of the expected formats, such as date formats.
+    UnknownFormat(String),
+    /// The number does not follow the exact numeric grammar, see
+    /// [ParseContextBuilder::strict_numeric].
+    MalformedNumber(String),
+    /// The field of the column (0-based) cannot be parsed. The line (1-based) is known
+    /// when the error is returned by a reader, such as [crate::CsvReader::read_strict].
+    InvalidField {
+        line: Option<usize>,
+        column: usize,
+        message: String,
+    },
+    /// The line (1-based) has another number of fields than expected, see
+    /// [ParseContextBuilder::strict_column_count]: more fields than the schema or than
+    /// the array it is parsed into, see [DefaultRowParser::parse_array], or another number
+    /// than the [ParseContextBuilder::column_count]. The line is known when the error is
+    /// returned by a reader.
+    WrongColumnCount {
+        line: Option<usize>,
+        expected: usize,
+        found: usize,
+    },
+    /// The field had to be unescaped, so it cannot be borrowed from the document, see
+    /// [StrParser].
+    UnescapedField,
+    /// The document had to be transcoded to UTF-8, so its rows cannot be borrowed, see
+    /// [crate::CsvReader::read_borrowed].
+    TranscodedDocument,
+    /// The settings declared by the document conflict with the others, such as a `sep="`
+    /// line. Returned by the readers whose rows are parsed lazily, such as
+    /// [crate::CsvReader::read_iter].
+    InvalidConfig(ConfigError),
 }
 
-impl Default for ParseContext {
-    fn default() -> Self {
-        Self { delimiter: COMMA }
+impl ParseError {
+    /// Sets the line (1-based) of an error returned without it, such as by a [RowParser].
+    pub(crate) fn at_line(self, line: usize) -> Self {
+        match self {
+            ParseError::InvalidField {
+                line: None,
+                column,
+                message,
+            } => ParseError::InvalidField {
+                line: Some(line),
+                column,
+                message,
+            },
+            ParseError::WrongColumnCount {
+                line: None,
+                expected,
+                found,
+            } => ParseError::WrongColumnCount {
+                line: Some(line),
+                expected,
+                found,
+            },
+            e => e,
+        }
     }
 }
 
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Parse error: TODO")
+        match self {
+            ParseError::UnknownLabel(label) => write!(f, "Parse error: unknown label '{}'", label),
+            ParseError::UnknownColumn(name) => write!(f, "Parse error: unknown column '{}'", name),
+            ParseError::UnexpectedHeader(header) => {
+                write!(f, "Parse error: unexpected header '{}'", header)
+            }
+            ParseError::FractionalInteger(value) => {
+                write!(f, "Parse error: '{}' is not an integer", value)
+            }
+            ParseError::TrailingAfterQuote { line } => write!(
+                f,
+                "Parse error: unexpected data after a closing quote on line {}",
+                line
+            ),
+            ParseError::Cancelled { line } => {
+                write!(f, "Parse error: cancelled before line {}", line)
+            }
+            ParseError::UnknownFormat(value) => {
+                write!(f, "Parse error: '{}' matches none of the formats", value)
+            }
+            ParseError::MalformedNumber(value) => {
+                write!(f, "Parse error: '{}' is not a well-formed number", value)
+            }
+            ParseError::InvalidField {
+                line: None,
+                column,
+                message,
+            } => {
+                write!(
+                    f,
+                    "Parse error: invalid field in column {}: {}",
+                    column, message
+                )
+            }
+            ParseError::InvalidField {
+                line: Some(line),
+                column,
+                message,
+            } => write!(
+                f,
+                "Parse error: invalid field in column {} on line {}: {}",
+                column, line, message
+            ),
+            ParseError::WrongColumnCount {
+                line: None,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Parse error: expected {} fields but found {}",
+                expected, found
+            ),
+            ParseError::WrongColumnCount {
+                line: Some(line),
+                expected,
+                found,
+            } => write!(
+                f,
+                "Parse error: expected {} fields but found {} on line {}",
+                expected, found, line
+            ),
+            ParseError::UnescapedField => {
+                write!(f, "Parse error: an unescaped field cannot be borrowed")
+            }
+            ParseError::TranscodedDocument => {
+                write!(f, "Parse error: a transcoded document cannot be borrowed")
+            }
+            ParseError::InvalidConfig(e) => write!(f, "Parse error: {}", e),
+        }
     }
 }
 
+impl Error for ParseError {}
+
 /// The [FieldParser] parses a single value (column) in a CSV row.
 pub trait FieldParser<T> {
     /// Parses the value in the CSV row, returning the parsed value if any.
     /// If the column is empty, returns None. If parsing fails, an error is returned.
     fn parse(span: &RowSpan) -> Result<T, Box<dyn Error>>;
+
+    /// Parses the value in the CSV row, honoring the settings of the [ParseContext].
+    /// By default, the context is ignored.
+    fn parse_with_context(span: &RowSpan, _context: &ParseContext) -> Result<T, Box<dyn Error>> {
+        Self::parse(span)
+    }
+}
+
+/// A parser that carries its own configuration, such as the inner delimiter of a [ListParser].
+/// Used to override the parser of a field in a [crate::schema].
+///
+/// Every [FieldParser] is also a [ConfiguredFieldParser].
+pub trait ConfiguredFieldParser<T> {
+    fn parse_field(&self, span: &FieldSpan, context: &ParseContext) -> Result<T, Box<dyn Error>>;
+}
+
+impl<T, P: FieldParser<T>> ConfiguredFieldParser<T> for P {
+    fn parse_field(&self, span: &FieldSpan, context: &ParseContext) -> Result<T, Box<dyn Error>> {
+        P::parse_with_context(span, context)
+    }
 }
 
 pub trait RowParser<S> {
     fn parse(row: &RowSpan, context: &ParseContext) -> S;
+
+    /// The number of fields of the schema, or `None` if any number of fields is read,
+    /// such as by the [DefaultRowParser].
+    fn field_count() -> Option<usize> {
+        None
+    }
+
+    /// Parses the row found at the line (1-based), and checks it if enabled by the
+    /// context, see [check_fields]. By default the row is split once more to be checked:
+    /// parsers override it to check the fields they split.
+    fn parse_checked(row: &RowSpan, line: usize, context: &ParseContext) -> Result<S, ParseError> {
+        check_fields(
+            RowSpanIterator::new(context, row),
+            Self::field_count(),
+            line,
+        )?;
+        Ok(Self::parse(row, context))
+    }
 }
 
 pub struct StringParser {}
@@ -48,6 +260,14 @@ impl FieldParser<String> for StringParser {
             Err(e) => Err(e.into()),
         }
     }
+
+    /// Parses the field without the trim characters of the context, if any.
+    fn parse_with_context(
+        span: &RowSpan,
+        context: &ParseContext,
+    ) -> Result<String, Box<dyn Error>> {
+        Self::parse(context.trim_field(span))
+    }
 }
 
 pub struct FloatParser<T: FastFloat> {
@@ -56,15 +276,62 @@ pub struct FloatParser<T: FastFloat> {
 
 impl<T: FastFloat> FieldParser<T> for FloatParser<T> {
     fn parse(span: &RowSpan) -> Result<T, Box<dyn Error>> {
-        let ss = String::from_utf8_lossy(span);
-        let s = ss.trim();
-        match fast_float::parse(s) {
+        Self::parse_with_context(span, &ParseContext::default())
+    }
+
+    fn parse_with_context(span: &RowSpan, context: &ParseContext) -> Result<T, Box<dyn Error>> {
+        let ss = String::from_utf8_lossy(context.trim_field(span));
+        let trimmed = context.trim_whitespace(&ss);
+
+        if context.strict_numeric && !context.number_format.is_well_formed(trimmed) {
+            return Err(ParseError::MalformedNumber(trimmed.to_string()).into());
+        }
+
+        let s = context.number_format.normalize(trimmed);
+
+        match fast_float::parse(s.as_ref()) {
             Ok(v) => Ok(v),
             Err(e) => Err(e.into()),
         }
     }
 }
 
+/// Parses integers, such as `42` or `-7`. Grouping separators are ignored, and
+/// a value with a decimal separator is an error, even if its fractional part is zero.
+pub struct IntParser<T: FromStr> {
+    marker: PhantomData<T>,
+}
+
+impl<T> FieldParser<T> for IntParser<T>
+where
+    T: FromStr,
+    T::Err: Error + 'static,
+{
+    fn parse(span: &RowSpan) -> Result<T, Box<dyn Error>> {
+        Self::parse_with_context(span, &ParseContext::default())
+    }
+
+    fn parse_with_context(span: &RowSpan, context: &ParseContext) -> Result<T, Box<dyn Error>> {
+        let ss = String::from_utf8_lossy(context.trim_field(span));
+        let s = context.trim_whitespace(&ss);
+
+        if s.contains(context.number_format.decimal_separator as char) {
+            return Err(ParseError::FractionalInteger(s.to_string()).into());
+        }
+
+        if context.strict_numeric && !context.number_format.is_well_formed(s) {
+            return Err(ParseError::MalformedNumber(s.to_string()).into());
+        }
+
+        match context.number_format.normalize(s).parse() {
+            Ok(v) => Ok(v),
+            Err(e) => Err(Box::new(e)),
+        }
+    }
+}
+
+/// Parses booleans. When the context has a [BoolStyle], the labels of this style are
+/// matched case-insensitively. Otherwise, only `true` and `false` are accepted.
 pub struct BoolParser {}
 
 impl FieldParser<bool> for BoolParser {
@@ -76,6 +343,247 @@ impl FieldParser<bool> for BoolParser {
             Err(e) => Err(e.into()),
         }
     }
+
+    fn parse_with_context(span: &RowSpan, context: &ParseContext) -> Result<bool, Box<dyn Error>> {
+        let Some(style) = context.bool_style else {
+            return Self::parse(span);
+        };
+
+        let ss = String::from_utf8_lossy(span);
+        let label = context.trim_whitespace(&ss);
+
+        style
+            .parse(label)
+            .ok_or_else(|| ParseError::UnknownLabel(label.to_string()).into())
+    }
+}
+
+/// Returns the bytes of the field verbatim, without UTF-8 validation, for binary-ish
+/// values such as hex or base64 blobs.
+pub struct BytesParser {}
+
+impl FieldParser<Vec<u8>> for BytesParser {
+    fn parse(span: &FieldSpan) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(span.to_vec())
+    }
+}
+
+/// Like [BytesParser], but removes the leading and trailing ASCII whitespace.
+pub struct TrimmedBytesParser {}
+
+impl FieldParser<Vec<u8>> for TrimmedBytesParser {
+    fn parse(span: &FieldSpan) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(span.trim_ascii().to_vec())
+    }
+}
+
+/// Parses a field containing a list of values separated by an inner delimiter, such as `a|b|c`.
+/// Each value is parsed with the [FieldParser] of `T`. An empty field is an empty list.
+pub struct ListParser<T> {
+    delimiter: u8,
+    marker: PhantomData<T>,
+}
+
+impl<T> ListParser<T> {
+    pub fn new(delimiter: u8) -> Self {
+        Self {
+            delimiter,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: IntoFieldParser<T>> ConfiguredFieldParser<Vec<T>> for ListParser<T> {
+    fn parse_field(
+        &self,
+        span: &FieldSpan,
+        context: &ParseContext,
+    ) -> Result<Vec<T>, Box<dyn Error>> {
+        if span.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        span.split(|b| *b == self.delimiter)
+            .map(|item| <T as IntoFieldParser<T>>::Parser::parse_with_context(item, context))
+            .collect()
+    }
+}
+
+/// Parses amounts of money, such as `$1,234.56`, `1.234,56 €` or `(1,234.56)`, into `f64`.
+///
+/// The currency symbol is removed, whether it precedes or follows the amount, and the
+/// number is parsed with the [NumberFormat] of the context. Amounts in parentheses are
+/// negative, as in accounting.
+pub struct CurrencyParser {
+    symbol: String,
+}
+
+impl CurrencyParser {
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+        }
+    }
+
+    fn strip_symbol<'a>(&self, s: &'a str, context: &ParseContext) -> &'a str {
+        let s = context.trim_whitespace(s);
+        let s = s.strip_prefix(self.symbol.as_str()).unwrap_or(s);
+        let s = s.strip_suffix(self.symbol.as_str()).unwrap_or(s);
+        context.trim_whitespace(s)
+    }
+}
+
+impl ConfiguredFieldParser<f64> for CurrencyParser {
+    fn parse_field(&self, span: &FieldSpan, context: &ParseContext) -> Result<f64, Box<dyn Error>> {
+        let s = context.trim_whitespace(std::str::from_utf8(span)?);
+
+        let (negative, s) = match s.strip_prefix('(').and_then(|s| s.strip_suffix(')')) {
+            Some(inner) => (true, inner),
+            None => match s.strip_prefix('-') {
+                Some(rest) => (true, rest),
+                None => (false, s),
+            },
+        };
+
+        let amount = self.strip_symbol(s, context);
+        // The sign can also follow the symbol, as in `$-5`.
+        let (negative, amount) = match amount.strip_prefix('-') {
+            Some(rest) => (!negative, rest),
+            None => (negative, amount),
+        };
+
+        let value: f64 = fast_float::parse(context.number_format.normalize(amount).as_ref())?;
+
+        Ok(if negative { -value } else { value })
+    }
+}
+
+/// Parses a geographic coordinate stored in a single field, such as `"37.77,-122.41"`, into
+/// a `(latitude, longitude)` tuple. Spaces around the numbers are ignored.
+///
+/// The numbers are always separated by a comma and use `.` as decimal separator. A value
+/// that isn't two numbers, or whose latitude or longitude is out of range, fails with
+/// [ParseError::UnknownFormat].
+pub struct CoordParser;
+
+impl FieldParser<(f64, f64)> for CoordParser {
+    fn parse(span: &RowSpan) -> Result<(f64, f64), Box<dyn Error>> {
+        let s = std::str::from_utf8(span)?.trim();
+        let malformed = || ParseError::UnknownFormat(s.to_string());
+
+        let (lat, lng) = s.split_once(',').ok_or_else(malformed)?;
+        let lat: f64 = fast_float::parse(lat.trim()).map_err(|_| malformed())?;
+        let lng: f64 = fast_float::parse(lng.trim()).map_err(|_| malformed())?;
+
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
+            return Err(malformed().into());
+        }
+
+        Ok((lat, lng))
+    }
+}
+
+/// What a [LookupParser] does with a value that is not a key of its table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unmapped {
+    /// Fails with [ParseError::UnknownLabel]. In a [crate::schema], the field is `None`.
+    Error,
+    /// Parses the value itself with the [FieldParser] of the type.
+    Passthrough,
+}
+
+/// Maps the values of a field through a lookup table, such as `US` to `United States`.
+/// Keys are matched after trimming the value.
+///
+/// The values can be of any type with a [FieldParser] in this crate, such as `String` or `f64`.
+pub struct LookupParser<T> {
+    table: HashMap<String, T>,
+    unmapped: Unmapped,
+}
+
+impl<T> LookupParser<T> {
+    pub fn new(table: HashMap<String, T>, unmapped: Unmapped) -> Self {
+        Self { table, unmapped }
+    }
+
+    pub fn from_pairs(
+        pairs: impl IntoIterator<Item = (&'static str, T)>,
+        unmapped: Unmapped,
+    ) -> Self {
+        let table = pairs.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        Self::new(table, unmapped)
+    }
+}
+
+impl<T: Clone + IntoFieldParser<T>> LookupParser<T> {
+    fn lookup(&self, span: &FieldSpan, context: &ParseContext) -> Result<T, Box<dyn Error>> {
+        let key = context.trim_whitespace(std::str::from_utf8(span)?);
+
+        if let Some(value) = self.table.get(key) {
+            return Ok(value.clone());
+        }
+
+        match self.unmapped {
+            Unmapped::Error => Err(ParseError::UnknownLabel(key.to_string()).into()),
+            Unmapped::Passthrough => {
+                <T as IntoFieldParser<T>>::Parser::parse_with_context(span, context)
+            }
+        }
+    }
+}
+
+// A generic implementation would conflict with the one for every FieldParser, since
+// another crate could implement FieldParser<T> for LookupParser<T> with its own T.
+macro_rules! impl_lookup_parser {
+    ($($type:ty),+) => {
+        $(
+            impl ConfiguredFieldParser<$type> for LookupParser<$type> {
+                fn parse_field(
+                    &self,
+                    span: &FieldSpan,
+                    context: &ParseContext,
+                ) -> Result<$type, Box<dyn Error>> {
+                    self.lookup(span, context)
+                }
+            }
+        )+
+    };
+}
+
+impl_lookup_parser!(bool, f32, f64, String, i8, i16, i32, i64, u8, u16, u32, u64);
+
+/// A [RowParser] that can read each field of the schema from any column, such as to
+/// resolve the columns by name. Implemented by the [crate::schema] macro.
+pub trait MappedRowParser<S> {
+    /// Parses the row, where the field `i` of the schema is read from the column
+    /// `positions[i]`, or is `None` if this position is `None`.
+    fn parse_mapped(row: &RowSpan, positions: &[Option<usize>], context: &ParseContext) -> S;
+}
+
+/// A [RowParser] that returns the first field that cannot be parsed as an error, instead
+/// of turning it into an empty field. See [crate::CsvReader::read_strict].
+pub trait StrictRowParser<S> {
+    /// Returns [ParseError::InvalidField] for the first non-empty field that cannot be
+    /// parsed.
+    fn parse_strict(row: &RowSpan, context: &ParseContext) -> Result<S, ParseError>;
+
+    /// Parses the row found at the line (1-based) like [StrictRowParser::parse_strict],
+    /// and checks it like [RowParser::parse_checked].
+    fn parse_strict_checked(
+        row: &RowSpan,
+        line: usize,
+        context: &ParseContext,
+    ) -> Result<S, ParseError>
+    where
+        Self: RowParser<S>,
+    {
+        check_fields(
+            RowSpanIterator::new(context, row),
+            Self::field_count(),
+            line,
+        )?;
+        Self::parse_strict(row, context)
+    }
 }
 
 pub trait IntoRowParser<S> {
@@ -86,6 +594,10 @@ impl IntoRowParser<DefaultSchema> for DefaultSchema {
     type Parser = DefaultRowParser;
 }
 
+impl IntoRowParser<CheckedSchema> for CheckedSchema {
+    type Parser = CheckedRowParser;
+}
+
 pub trait IntoFieldParser<T> {
     type Parser: FieldParser<T>;
 }
@@ -106,18 +618,159 @@ impl IntoFieldParser<String> for String {
     type Parser = StringParser;
 }
 
+impl IntoFieldParser<Vec<u8>> for Vec<u8> {
+    type Parser = BytesParser;
+}
+
+macro_rules! impl_into_int_parser {
+    ($($type:ty),+) => {
+        $(
+            impl IntoFieldParser<$type> for $type {
+                type Parser = IntParser<$type>;
+            }
+        )+
+    };
+}
+
+impl_into_int_parser!(i8, i16, i32, i64, u8, u16, u32, u64);
+
+// Zero is rejected by the `FromStr` implementation of the non-zero integers.
+impl_into_int_parser!(
+    NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64
+);
+
 pub fn try_parse<T: IntoFieldParser<T>>(span: &FieldSpan) -> Option<T> {
-    let s = <T as IntoFieldParser<T>>::Parser::parse(span);
-    match s {
-        Ok(v) => Some(v),
-        Err(_) => None,
+    <T as IntoFieldParser<T>>::Parser::parse(span).ok()
+}
+
+pub fn try_parse_with_context<T: IntoFieldParser<T>>(
+    span: &FieldSpan,
+    context: &ParseContext,
+) -> Option<T> {
+    <T as IntoFieldParser<T>>::Parser::parse_with_context(span, context).ok()
+}
+
+/// Parses the field of the column (0-based), returning [ParseError::InvalidField] if it
+/// cannot be parsed. Used by [crate::schema].
+pub fn parse_column<T: IntoFieldParser<T>>(
+    span: &FieldSpan,
+    column: usize,
+    context: &ParseContext,
+) -> Result<T, ParseError> {
+    <T as IntoFieldParser<T>>::Parser::parse_with_context(span, context).map_err(|e| {
+        ParseError::InvalidField {
+            line: None,
+            column,
+            message: e.to_string(),
+        }
+    })
+}
+
+/// Parses the field of the column (0-based) with the parser, like [parse_column].
+pub fn parse_column_with_parser<T>(
+    parser: &impl ConfiguredFieldParser<T>,
+    span: &FieldSpan,
+    column: usize,
+    context: &ParseContext,
+) -> Result<T, ParseError> {
+    parser
+        .parse_field(span, context)
+        .map_err(|e| ParseError::InvalidField {
+            line: None,
+            column,
+            message: e.to_string(),
+        })
+}
+
+/// Checks the row found at the line (1-based) once its fields are parsed with the
+/// iterator, if enabled by the context: its quotes, see
+/// [ParseContextBuilder::strict_quotes], and its number of fields, see
+/// [ParseContextBuilder::strict_column_count]. The fields left are split without being
+/// parsed, so that the row is only split once. Used by [crate::schema].
+///
+/// `field_count` is the number of fields of the schema, if it has a fixed width.
+pub fn check_fields(
+    mut iterator: RowSpanIterator,
+    field_count: Option<usize>,
+    line: usize,
+) -> Result<(), ParseError> {
+    let context = iterator.context;
+    if !context.strict_quotes() && !context.strict_column_count() {
+        return Ok(());
+    }
+
+    let found = iterator.position() + iterator.by_ref().count();
+
+    if context.strict_quotes() && iterator.has_trailing_after_quote() {
+        return Err(ParseError::TrailingAfterQuote { line });
+    }
+
+    if context.strict_column_count() {
+        let wrong = match (context.column_count(), field_count) {
+            (Some(expected), _) => (found != expected).then_some(expected),
+            (None, Some(expected)) => (found > expected).then_some(expected),
+            (None, None) => None,
+        };
+        if let Some(expected) = wrong {
+            return Err(ParseError::WrongColumnCount {
+                line: Some(line),
+                expected,
+                found,
+            });
+        }
     }
+
+    Ok(())
+}
+
+/// Returns the field as a string, without the trim characters and the whitespace of
+/// the context, see [ParseContextBuilder::ascii_trim]. Used by [crate::csv_enum].
+pub fn trim_label<'a>(
+    span: &'a FieldSpan,
+    context: &ParseContext,
+) -> Result<&'a str, std::str::Utf8Error> {
+    Ok(context.trim_whitespace(std::str::from_utf8(context.trim_field(span))?))
 }
 
+/// Returns true if the field is null, rather than a value to parse: an unquoted empty
+/// field when [ParseContext::empty_as_null] is enabled. Used by [crate::schema].
+pub fn is_null(span: &FieldSpan, quoted: bool, context: &ParseContext) -> bool {
+    context.empty_as_null && !quoted && context.is_empty_field(span)
+}
+
+pub fn try_parse_with_parser<T>(
+    parser: &impl ConfiguredFieldParser<T>,
+    span: &FieldSpan,
+    context: &ParseContext,
+) -> Option<T> {
+    parser.parse_field(span, context).ok()
+}
+
+/// Splits a row into its fields.
+///
+/// Each delimiter separates two fields, so that a row ending with a delimiter has an empty
+/// last field, unless the context says otherwise, see [TrailingDelimiter]. An empty row
+/// has no field.
+///
+/// Fields that start with the quote character of the [ParseContext] are quoted: the
+/// delimiters they contain are part of the value, the surrounding quotes are removed,
+/// and escaped quotes (`""`) are collapsed into a single quote. Fields are borrowed
+/// from the row, unless they had to be unescaped.
+///
+/// If the context has an escape character, such as `\`, it makes the next character part
+/// of an unquoted field: `a\,b` is the single field `a,b`. The escape character is not
+/// special inside quoted fields, where quotes are escaped by doubling them.
+///
+/// Quotes after the start of a field are literal, unless the context enables
+/// [ParseContextBuilder::quote_anywhere].
 pub struct RowSpanIterator<'a> {
     context: &'a ParseContext,
     row: &'a RowSpan,
     offset: usize,
+    trailing_after_quote: bool,
+    quoted: bool,
+    after_delimiter: bool,
+    position: usize,
 }
 
 impl<'a> RowSpanIterator<'a> {
@@ -126,33 +779,213 @@ impl<'a> RowSpanIterator<'a> {
             context,
             row,
             offset: 0,
+            trailing_after_quote: false,
+            quoted: false,
+            after_delimiter: false,
+            position: 0,
+        }
+    }
+
+    /// Returns true if the last field returned by the iterator was quoted.
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
+    /// Returns true if a quoted field had data between its closing quote and the next
+    /// delimiter, such as `"quoted"garbage`. This data is appended to the value.
+    pub fn has_trailing_after_quote(&self) -> bool {
+        self.trailing_after_quote
+    }
+
+    /// Returns the number of fields returned so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the offset after the delimiter found at `index`, if any, or to the end of the row.
+    fn advance(&mut self, index: Option<usize>) {
+        self.after_delimiter = index.is_some();
+        self.offset = match index {
+            Some(i) => self.context.field_start(self.row, i + 1),
+            None => self.row.len(),
+        };
+    }
+
+    fn next_quoted(&mut self, quote: u8) -> Cow<'a, FieldSpan> {
+        let row = self.row;
+        let start = self.offset + 1;
+
+        // Only allocated when the value contains escaped quotes or trailing data.
+        let mut unescaped: Option<Vec<u8>> = None;
+        let mut segment = start;
+
+        loop {
+            let Some(found) = memchr::memchr(quote, &row[segment..]) else {
+                // Unterminated quoted field: the rest of the row is the value.
+                self.advance(None);
+                return match unescaped {
+                    Some(mut v) => {
+                        v.extend_from_slice(&row[segment..]);
+                        Cow::Owned(v)
+                    }
+                    None => Cow::Borrowed(&row[start..]),
+                };
+            };
+
+            let position = segment + found;
+
+            if row.get(position + 1) == Some(&quote) {
+                unescaped
+                    .get_or_insert_with(Vec::new)
+                    .extend_from_slice(&row[segment..=position]);
+                segment = position + 2;
+                continue;
+            }
+
+            // The closing quote. Any data up to the next delimiter is appended to the value.
+            let delimiter = memchr::memchr(self.context.delimiter, &row[position + 1..])
+                .map(|i| position + 1 + i);
+            let trailing = &row[position + 1..delimiter.unwrap_or(row.len())];
+            self.advance(delimiter);
+
+            if !trailing.is_empty() {
+                self.trailing_after_quote = true;
+            }
+
+            if unescaped.is_none() && trailing.is_empty() {
+                return Cow::Borrowed(&row[start..position]);
+            }
+
+            let mut v = unescaped.unwrap_or_default();
+            v.extend_from_slice(&row[segment..position]);
+            v.extend_from_slice(trailing);
+            return Cow::Owned(v);
+        }
+    }
+}
+
+impl<'a> RowSpanIterator<'a> {
+    /// Returns the field at the offset, which contains a quote after its start. The quotes
+    /// are removed, and the delimiters between them are part of the value.
+    fn next_quoted_anywhere(&mut self, quote: u8) -> Cow<'a, FieldSpan> {
+        let row = self.row;
+        let mut value = Vec::new();
+        let mut in_quotes = false;
+        let mut i = self.offset;
+
+        while i < row.len() {
+            let c = row[i];
+            if c == quote {
+                if in_quotes && row.get(i + 1) == Some(&quote) {
+                    value.push(quote);
+                    i += 1;
+                } else {
+                    in_quotes = !in_quotes;
+                }
+            } else if c == self.context.delimiter && !in_quotes {
+                break;
+            } else {
+                value.push(c);
+            }
+            i += 1;
+        }
+
+        self.advance((i < row.len()).then_some(i));
+        Cow::Owned(value)
+    }
+
+    /// Returns the unquoted field at the offset, where the delimiter or escape character
+    /// following an escape character is part of the value.
+    fn next_escaped(&mut self, escape: u8) -> Cow<'a, FieldSpan> {
+        let row = self.row;
+        let start = self.offset;
+
+        // Only allocated when the value contains escaped characters.
+        let mut unescaped: Option<Vec<u8>> = None;
+        let mut segment = start;
+
+        loop {
+            let found = memchr::memchr2(self.context.delimiter, escape, &row[segment..])
+                .map(|i| segment + i);
+
+            match found {
+                Some(i) if row[i] == escape && i + 1 < row.len() => {
+                    let v = unescaped.get_or_insert_with(Vec::new);
+                    v.extend_from_slice(&row[segment..i]);
+                    v.push(row[i + 1]);
+                    segment = i + 2;
+                }
+                _ => {
+                    // An escape character at the end of the row is kept as is.
+                    let delimiter = found.filter(|i| row[*i] == self.context.delimiter);
+                    let end = delimiter.unwrap_or(row.len());
+                    self.advance(delimiter);
+
+                    return match unescaped {
+                        Some(mut v) => {
+                            v.extend_from_slice(&row[segment..end]);
+                            Cow::Owned(v)
+                        }
+                        None => Cow::Borrowed(&row[start..end]),
+                    };
+                }
+            }
         }
     }
 }
 
 impl<'a> Iterator for RowSpanIterator<'a> {
-    type Item = &'a FieldSpan;
+    type Item = Cow<'a, FieldSpan>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some(index) = memchr::memchr(self.context.delimiter, &self.row[self.offset..]) {
-            let res = &self.row[self.offset..self.offset + index];
-            self.offset += index + 1;
-            return Some(res);
-        }
+        let field = self.next_field()?;
+        self.position += 1;
+        Some(field)
+    }
+}
 
+impl<'a> RowSpanIterator<'a> {
+    fn next_field(&mut self) -> Option<Cow<'a, FieldSpan>> {
         let remaining = &self.row[self.offset..];
-        if !remaining.is_empty() {
-            return Some(remaining);
+        if remaining.is_empty() {
+            // A row ending with a delimiter has a trailing empty field.
+            self.quoted = false;
+            if std::mem::take(&mut self.after_delimiter)
+                && self.context.trailing_delimiter() == TrailingDelimiter::EmptyField
+            {
+                return Some(Cow::Borrowed(remaining));
+            }
+            return None;
         }
 
-        None
+        self.quoted = self.context.quote == Some(remaining[0]);
+        if self.quoted {
+            return Some(self.next_quoted(remaining[0]));
+        }
+
+        if let Some(quote) = self.context.quote.filter(|_| self.context.quote_anywhere) {
+            let found = memchr::memchr2(self.context.delimiter, quote, remaining);
+            if found.is_some_and(|i| remaining[i] == quote) {
+                return Some(self.next_quoted_anywhere(quote));
+            }
+        }
+
+        if let Some(escape) = self.context.escape {
+            return Some(self.next_escaped(escape));
+        }
+
+        let index = memchr::memchr(self.context.delimiter, remaining);
+        let field = &remaining[..index.unwrap_or(remaining.len())];
+        self.advance(index.map(|i| self.offset + i));
+
+        Some(Cow::Borrowed(field))
     }
 }
 
 #[cfg(test)]
 mod test {
     mod bool_parser {
-        use crate::parser::{BoolParser, FieldParser};
+        use crate::parser::{BoolParser, BoolStyle, FieldParser, ParseContext, ParseError};
 
         #[test]
         fn parse_true_value_returns_ok() {
@@ -173,10 +1006,62 @@ mod test {
             let result = BoolParser::parse(b"nope");
             assert!(result.is_err());
         }
+
+        fn parse_with_style(span: &[u8], style: BoolStyle) -> Option<bool> {
+            let context = ParseContext::builder().bool_style(style).build().unwrap();
+            BoolParser::parse_with_context(span, &context).ok()
+        }
+
+        #[test]
+        fn true_false_style() {
+            assert_eq!(Some(true), parse_with_style(b"TRUE", BoolStyle::TrueFalse));
+            assert_eq!(
+                Some(false),
+                parse_with_style(b"False", BoolStyle::TrueFalse)
+            );
+            assert_eq!(None, parse_with_style(b"t", BoolStyle::TrueFalse));
+        }
+
+        #[test]
+        fn yes_no_style() {
+            assert_eq!(Some(true), parse_with_style(b"Y", BoolStyle::YesNo));
+            assert_eq!(Some(true), parse_with_style(b"yes", BoolStyle::YesNo));
+            assert_eq!(Some(false), parse_with_style(b" n", BoolStyle::YesNo));
+            assert_eq!(None, parse_with_style(b"true", BoolStyle::YesNo));
+        }
+
+        #[test]
+        fn one_zero_style() {
+            assert_eq!(Some(true), parse_with_style(b"1", BoolStyle::OneZero));
+            assert_eq!(Some(false), parse_with_style(b"0", BoolStyle::OneZero));
+            assert_eq!(None, parse_with_style(b"2", BoolStyle::OneZero));
+        }
+
+        #[test]
+        fn tf_style() {
+            assert_eq!(Some(true), parse_with_style(b"T", BoolStyle::TF));
+            assert_eq!(Some(false), parse_with_style(b"f", BoolStyle::TF));
+            assert_eq!(None, parse_with_style(b"y", BoolStyle::TF));
+        }
+
+        #[test]
+        fn unknown_label_returns_err() {
+            let context = ParseContext::builder()
+                .bool_style(BoolStyle::YesNo)
+                .build()
+                .unwrap();
+
+            let err = BoolParser::parse_with_context(b"maybe", &context).unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::UnknownLabel("maybe".into())),
+                err.downcast_ref::<ParseError>()
+            );
+        }
     }
 
     mod float_parser {
-        use crate::parser::{FieldParser, FloatParser};
+        use crate::parser::{FieldParser, FloatParser, ParseContext};
 
         #[test]
         fn parse_valid_value_returns_ok() {
@@ -190,44 +1075,843 @@ mod test {
             let result = FloatParser::<f32>::parse(b"nope");
             assert!(result.is_err());
         }
+
+        #[test]
+        fn parse_with_decimal_comma_returns_ok() {
+            let context = ParseContext::builder()
+                .delimiter(b';')
+                .decimal_separator(b',')
+                .build()
+                .unwrap();
+
+            let result = FloatParser::<f64>::parse_with_context(b"0,32", &context);
+            assert_eq!(0.32, result.unwrap());
+        }
     }
 
-    mod default_row_parser {
-        use crate::parser::{DefaultRowParser, FieldValue, ParseContext, RowParser};
+    mod int_parser {
+        use crate::parser::{FieldParser, IntParser, ParseContext, ParseError};
+
+        fn grouping_context() -> ParseContext {
+            ParseContext::builder()
+                .grouping_separator(Some(b','))
+                .build()
+                .unwrap()
+        }
 
         #[test]
-        fn parse_returns_correct_values() {
-            let row = b" Hello,  world! ,30.2 ";
+        fn parse_valid_i64_returns_ok() {
+            assert_eq!(-42, IntParser::<i64>::parse(b" -42 ").unwrap());
+        }
 
-            let context: ParseContext = ParseContext::default();
+        #[test]
+        fn parse_valid_u32_returns_ok() {
+            assert_eq!(42, IntParser::<u32>::parse(b"42").unwrap());
+        }
 
-            let result = DefaultRowParser::parse(row, &context).fields;
+        #[test]
+        fn parse_invalid_value_returns_err() {
+            assert!(IntParser::<i64>::parse(b"nope").is_err());
+            assert!(IntParser::<u32>::parse(b"").is_err());
+        }
 
-            assert_eq!(3, result.len());
+        #[test]
+        fn parse_negative_value_into_unsigned_returns_err() {
+            assert!(IntParser::<u32>::parse(b"-1").is_err());
+        }
 
-            assert_eq!(Some(FieldValue::String(" Hello".to_string())), result[0]);
-            assert_eq!(Some(FieldValue::String("  world! ".to_string())), result[1]);
-            assert_eq!(Some(FieldValue::Float(30.2f64)), result[2]);
+        #[test]
+        fn parse_overflowing_value_returns_err() {
+            assert!(IntParser::<u32>::parse(b"4294967296").is_err());
+            assert!(IntParser::<i64>::parse(b"9223372036854775808").is_err());
         }
 
         #[test]
-        fn parse_handle_empty_columns() {
-            let row = b"Hello,world!,30.2";
+        fn parse_with_grouping_separator_returns_ok() {
+            let result = IntParser::<i64>::parse_with_context(b"1,000", &grouping_context());
+            assert_eq!(1000, result.unwrap());
+        }
 
-            let context: ParseContext = ParseContext::default();
+        #[test]
+        fn parse_with_decimal_separator_returns_err() {
+            let err =
+                IntParser::<i64>::parse_with_context(b"1,000.5", &grouping_context()).unwrap_err();
 
-            let result = DefaultRowParser::parse(row, &context).fields;
+            assert_eq!(
+                Some(&ParseError::FractionalInteger("1,000.5".into())),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+    }
 
-            assert_eq!(3, result.len());
+    mod non_zero_int_parser {
+        use std::num::{NonZeroI32, NonZeroU64};
 
-            assert_eq!(Some(FieldValue::String("Hello".to_string())), result[0]);
-            assert_eq!(Some(FieldValue::String("world!".to_string())), result[1]);
-            assert_eq!(Some(FieldValue::Float(30.2f64)), result[2]);
+        use crate::parser::{try_parse, FieldParser, IntParser};
+
+        #[test]
+        fn parse_non_zero_returns_ok() {
+            assert_eq!(NonZeroU64::new(42), try_parse::<NonZeroU64>(b"42"));
+            assert_eq!(NonZeroI32::new(-7), try_parse::<NonZeroI32>(b"-7"));
+        }
+
+        #[test]
+        fn parse_zero_returns_err() {
+            assert!(IntParser::<NonZeroU64>::parse(b"0").is_err());
+            assert!(IntParser::<NonZeroI32>::parse(b"-0").is_err());
         }
     }
 
-    mod string_parser {
-        use crate::parser::{FieldParser, StringParser};
+    mod strict_numeric {
+        use crate::{
+            parser::{FieldParser, FieldValue, FloatParser, IntParser, ParseContext, ParseError},
+            CsvReader, DefaultSchema,
+        };
+
+        fn strict() -> ParseContext {
+            ParseContext::builder()
+                .grouping_separator(Some(b','))
+                .strict_numeric(true)
+                .build()
+                .unwrap()
+        }
+
+        #[test]
+        fn malformed_numbers_return_err() {
+            for value in ["1,,234", "1.2.3", "--5", "5.", "1,234,", "1e", "inf"] {
+                let err = FloatParser::<f64>::parse_with_context(value.as_bytes(), &strict())
+                    .unwrap_err();
+
+                assert_eq!(
+                    Some(&ParseError::MalformedNumber(value.into())),
+                    err.downcast_ref::<ParseError>()
+                );
+            }
+
+            let err = IntParser::<i64>::parse_with_context(b"1,,234", &strict()).unwrap_err();
+            assert_eq!(
+                Some(&ParseError::MalformedNumber("1,,234".into())),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn well_formed_numbers_return_ok() {
+            let float = FloatParser::<f64>::parse_with_context(b" -1,234.5 ", &strict());
+            let int = IntParser::<i64>::parse_with_context(b"+1,234", &strict());
+
+            assert_eq!(-1234.5, float.unwrap());
+            assert_eq!(1234, int.unwrap());
+        }
+
+        #[test]
+        fn lenient_by_default() {
+            let context = ParseContext::builder()
+                .grouping_separator(Some(b','))
+                .build()
+                .unwrap();
+
+            let result = FloatParser::<f64>::parse_with_context(b"1,,234", &context);
+
+            assert_eq!(1234f64, result.unwrap());
+        }
+
+        #[test]
+        fn malformed_numbers_are_strings_in_default_schema() {
+            let context = ParseContext::builder()
+                .strict_numeric(true)
+                .build()
+                .unwrap();
+
+            let rows = CsvReader::<DefaultSchema>::default()
+                .with_context(context)
+                .read(b"a,b\n1.5,inf\n")
+                .unwrap();
+
+            assert_eq!(Some(FieldValue::Float(1.5)), rows[0].fields()[0]);
+            assert_eq!(Some(FieldValue::String("inf".into())), rows[0].fields()[1]);
+        }
+    }
+
+    mod float_parser_grouping {
+        use crate::parser::{FieldParser, FloatParser, ParseContext};
+
+        #[test]
+        fn parse_with_grouping_separator_returns_ok() {
+            let context = ParseContext::builder()
+                .delimiter(b';')
+                .decimal_separator(b',')
+                .grouping_separator(Some(b'.'))
+                .build()
+                .unwrap();
+
+            let result = FloatParser::<f64>::parse_with_context(b"1.234,5", &context);
+            assert_eq!(1234.5, result.unwrap());
+        }
+    }
+
+    mod default_row_parser {
+        use crate::parser::{DefaultRowParser, FieldValue, ParseContext, RowParser};
+
+        #[test]
+        fn parse_returns_correct_values() {
+            let row = b" Hello,  world! ,30.2 ";
+
+            let context: ParseContext = ParseContext::default();
+
+            let result = DefaultRowParser::parse(row, &context).fields;
+
+            assert_eq!(3, result.len());
+
+            assert_eq!(Some(FieldValue::String(" Hello".to_string())), result[0]);
+            assert_eq!(Some(FieldValue::String("  world! ".to_string())), result[1]);
+            assert_eq!(Some(FieldValue::Float(30.2f64)), result[2]);
+        }
+
+        #[test]
+        fn parse_quoted_values() {
+            let row = b"\"Smith, John\",\"1.5\"";
+
+            let context: ParseContext = ParseContext::default();
+
+            let result = DefaultRowParser::parse(row, &context).fields;
+
+            assert_eq!(2, result.len());
+            assert_eq!(
+                Some(FieldValue::String("Smith, John".to_string())),
+                result[0]
+            );
+            assert_eq!(Some(FieldValue::Float(1.5f64)), result[1]);
+        }
+
+        #[test]
+        fn parse_keeps_whitespace_only_fields_by_default() {
+            let context = ParseContext::builder().delimiter(b';').build().unwrap();
+
+            let result = DefaultRowParser::parse(b" ; 5\n", &context).fields;
+
+            assert_eq!(Some(FieldValue::String(" ".to_string())), result[0]);
+            assert_eq!(Some(FieldValue::Int(5)), result[1]);
+        }
+
+        #[test]
+        fn parse_with_whitespace_as_empty() {
+            let context = ParseContext::builder()
+                .delimiter(b';')
+                .whitespace_as_empty(true)
+                .build()
+                .unwrap();
+
+            let result = DefaultRowParser::parse(b" ; 5\n", &context).fields;
+
+            assert_eq!(2, result.len());
+            assert_eq!(None, result[0]);
+            assert_eq!(Some(FieldValue::Int(5)), result[1]);
+        }
+
+        #[test]
+        fn parse_with_skip_initial_space() {
+            let row = b"a, b,  30";
+
+            let context = ParseContext::builder()
+                .skip_initial_space(true)
+                .build()
+                .unwrap();
+
+            let result = DefaultRowParser::parse(row, &context).fields;
+
+            assert_eq!(Some(FieldValue::String("a".to_string())), result[0]);
+            assert_eq!(Some(FieldValue::String("b".to_string())), result[1]);
+            assert_eq!(Some(FieldValue::Int(30)), result[2]);
+        }
+
+        #[test]
+        fn parse_handle_empty_columns() {
+            let row = b"Hello,world!,30.2";
+
+            let context: ParseContext = ParseContext::default();
+
+            let result = DefaultRowParser::parse(row, &context).fields;
+
+            assert_eq!(3, result.len());
+
+            assert_eq!(Some(FieldValue::String("Hello".to_string())), result[0]);
+            assert_eq!(Some(FieldValue::String("world!".to_string())), result[1]);
+            assert_eq!(Some(FieldValue::Float(30.2f64)), result[2]);
+        }
+    }
+
+    mod list_parser {
+        use crate::parser::{ConfiguredFieldParser, ListParser, ParseContext};
+
+        #[test]
+        fn parse_pipe_separated_values() {
+            let context = ParseContext::default();
+            let result = ListParser::<f64>::new(b'|').parse_field(b"1|2.5|3", &context);
+
+            assert_eq!(vec![1f64, 2.5, 3f64], result.unwrap());
+        }
+
+        #[test]
+        fn parse_empty_field_returns_empty_list() {
+            let context = ParseContext::default();
+            let result = ListParser::<String>::new(b'|').parse_field(b"", &context);
+
+            assert!(result.unwrap().is_empty());
+        }
+
+        #[test]
+        fn parse_invalid_value_returns_err() {
+            let context = ParseContext::default();
+            let result = ListParser::<f64>::new(b'|').parse_field(b"1|nope", &context);
+
+            assert!(result.is_err());
+        }
+    }
+
+    mod lookup_parser {
+        use crate::parser::{
+            ConfiguredFieldParser, LookupParser, ParseContext, ParseError, Unmapped,
+        };
+
+        fn countries(unmapped: Unmapped) -> LookupParser<String> {
+            LookupParser::from_pairs(
+                [
+                    ("US", "United States".to_string()),
+                    ("FR", "France".to_string()),
+                ],
+                unmapped,
+            )
+        }
+
+        #[test]
+        fn parse_known_key() {
+            let context = ParseContext::default();
+            let result = countries(Unmapped::Error).parse_field(b" FR", &context);
+
+            assert_eq!("France", result.unwrap());
+        }
+
+        #[test]
+        fn parse_unknown_key_returns_err() {
+            let context = ParseContext::default();
+            let err = countries(Unmapped::Error)
+                .parse_field(b"DE", &context)
+                .unwrap_err();
+
+            assert_eq!(
+                Some(&ParseError::UnknownLabel("DE".into())),
+                err.downcast_ref::<ParseError>()
+            );
+        }
+
+        #[test]
+        fn parse_unknown_key_with_passthrough() {
+            let context = ParseContext::default();
+            let result = countries(Unmapped::Passthrough).parse_field(b"DE", &context);
+
+            assert_eq!("DE", result.unwrap());
+        }
+    }
+
+    mod coord_parser {
+        use crate::parser::{CoordParser, FieldParser, ParseError};
+
+        #[test]
+        fn parse_coordinate_returns_ok() {
+            assert_eq!(
+                (37.77, -122.41),
+                CoordParser::parse(b"37.77,-122.41").unwrap()
+            );
+            assert_eq!((-1.5, 2f64), CoordParser::parse(b" -1.5 , 2 ").unwrap());
+        }
+
+        #[test]
+        fn parse_malformed_coordinate_returns_err() {
+            for value in ["37.77", "37.77;-122.41", "north,-122.41", "91,0", "0,181"] {
+                let err = CoordParser::parse(value.as_bytes()).unwrap_err();
+
+                assert_eq!(
+                    Some(&ParseError::UnknownFormat(value.into())),
+                    err.downcast_ref::<ParseError>()
+                );
+            }
+        }
+    }
+
+    mod currency_parser {
+        use crate::parser::{ConfiguredFieldParser, CurrencyParser, ParseContext};
+
+        fn dollars() -> ParseContext {
+            ParseContext::builder()
+                .grouping_separator(Some(b','))
+                .build()
+                .unwrap()
+        }
+
+        fn euros() -> ParseContext {
+            ParseContext::builder()
+                .delimiter(b';')
+                .decimal_separator(b',')
+                .grouping_separator(Some(b'.'))
+                .build()
+                .unwrap()
+        }
+
+        #[test]
+        fn parse_leading_symbol() {
+            let result = CurrencyParser::new("$").parse_field(b"$1,234.56", &dollars());
+
+            assert_eq!(1234.56, result.unwrap());
+        }
+
+        #[test]
+        fn parse_trailing_symbol() {
+            let parser = CurrencyParser::new("€");
+
+            assert_eq!(
+                1234.56,
+                parser
+                    .parse_field("1.234,56 €".as_bytes(), &euros())
+                    .unwrap()
+            );
+            assert_eq!(
+                1234.56,
+                parser
+                    .parse_field("€1.234,56".as_bytes(), &euros())
+                    .unwrap()
+            );
+        }
+
+        #[test]
+        fn parse_negative_amounts() {
+            let parser = CurrencyParser::new("$");
+
+            assert_eq!(
+                -1234.56,
+                parser.parse_field(b"(1,234.56)", &dollars()).unwrap()
+            );
+            assert_eq!(-5f64, parser.parse_field(b"($5)", &dollars()).unwrap());
+            assert_eq!(-5f64, parser.parse_field(b"-$5", &dollars()).unwrap());
+            assert_eq!(-5f64, parser.parse_field(b"$-5", &dollars()).unwrap());
+        }
+
+        #[test]
+        fn parse_invalid_amount_returns_err() {
+            let parser = CurrencyParser::new("$");
+
+            assert!(parser.parse_field(b"$abc", &dollars()).is_err());
+            assert!(parser.parse_field(b"", &dollars()).is_err());
+        }
+    }
+
+    mod row_span_iterator {
+        use crate::parser::{
+            check_fields, ParseContext, ParseError, RowSpanIterator, TrailingDelimiter,
+        };
+
+        #[test]
+        fn trailing_delimiter_yields_empty_field() {
+            let context = ParseContext::default();
+
+            let fields: Vec<_> = RowSpanIterator::new(&context, b"a,,\"b\",").collect();
+            assert_eq!(fields, vec![&b"a"[..], b"", b"b", b""]);
+
+            assert_eq!(2, RowSpanIterator::new(&context, b",").count());
+            assert_eq!(0, RowSpanIterator::new(&context, b"").count());
+        }
+
+        #[test]
+        fn trailing_delimiter_after_initial_space_yields_empty_field() {
+            let context = ParseContext::builder()
+                .skip_initial_space(true)
+                .build()
+                .unwrap();
+
+            let fields: Vec<_> = RowSpanIterator::new(&context, b"a,  ").collect();
+
+            assert_eq!(fields, vec![&b"a"[..], b""]);
+        }
+
+        #[test]
+        fn position_counts_the_fields_returned() {
+            let context = ParseContext::default();
+            let mut iterator = RowSpanIterator::new(&context, b"a,b,c");
+
+            assert_eq!(0, iterator.position());
+            iterator.next();
+            assert_eq!(1, iterator.position());
+            iterator.by_ref().for_each(drop);
+            assert_eq!(3, iterator.position());
+        }
+
+        #[test]
+        fn check_fields_counts_the_remaining_fields() {
+            let context = ParseContext::builder()
+                .strict_column_count(true)
+                .build()
+                .unwrap();
+            let mut iterator = RowSpanIterator::new(&context, b"a,b,c");
+            iterator.next();
+
+            assert_eq!(
+                Err(ParseError::WrongColumnCount {
+                    line: Some(7),
+                    expected: 2,
+                    found: 3
+                }),
+                check_fields(iterator, Some(2), 7)
+            );
+            assert_eq!(
+                Ok(()),
+                check_fields(RowSpanIterator::new(&context, b"a,b"), Some(2), 7)
+            );
+        }
+
+        #[test]
+        fn trailing_delimiter_policies() {
+            let empty_field = ParseContext::builder()
+                .trailing_delimiter(TrailingDelimiter::EmptyField)
+                .build()
+                .unwrap();
+            let ignore = ParseContext::builder()
+                .trailing_delimiter(TrailingDelimiter::Ignore)
+                .build()
+                .unwrap();
+
+            let fields: Vec<_> = RowSpanIterator::new(&empty_field, b"a,b,").collect();
+            assert_eq!(fields, vec![&b"a"[..], b"b", b""]);
+
+            let fields: Vec<_> = RowSpanIterator::new(&ignore, b"a,b,").collect();
+            assert_eq!(fields, vec![&b"a"[..], b"b"]);
+            let fields: Vec<_> = RowSpanIterator::new(&ignore, b"a,,b").collect();
+            assert_eq!(fields, vec![&b"a"[..], b"", b"b"]);
+        }
+
+        #[test]
+        fn interior_quotes_are_literal_by_default() {
+            let context = ParseContext::default();
+            let fields: Vec<_> = RowSpanIterator::new(&context, br#"abc"def"ghi,x"y,z"#).collect();
+
+            assert_eq!(fields, vec![&br#"abc"def"ghi"#[..], br#"x"y"#, b"z"]);
+        }
+
+        #[test]
+        fn interior_quotes_are_stripped_with_quote_anywhere() {
+            let context = ParseContext::builder()
+                .quote_anywhere(true)
+                .build()
+                .unwrap();
+            let row = br#"abc"def"ghi,a"b,c"d,e"x""y",z"#;
+
+            let fields: Vec<_> = RowSpanIterator::new(&context, row).collect();
+
+            assert_eq!(fields, vec![&b"abcdefghi"[..], b"ab,cd", br#"ex"y"#, b"z"]);
+        }
+
+        #[test]
+        fn escaped_delimiter_in_unquoted_field() {
+            let context = ParseContext::builder().escape(Some(b'\\')).build().unwrap();
+            let fields: Vec<_> = RowSpanIterator::new(&context, br"a\,b,c\\d,e\").collect();
+
+            assert_eq!(fields, vec![&b"a,b"[..], br"c\d", br"e\"]);
+        }
+
+        #[test]
+        fn escape_is_literal_in_quoted_field() {
+            let context = ParseContext::builder().escape(Some(b'\\')).build().unwrap();
+            let fields: Vec<_> = RowSpanIterator::new(&context, br#""a\,b",c"#).collect();
+
+            assert_eq!(fields, vec![&br"a\,b"[..], b"c"]);
+        }
+
+        #[test]
+        fn escape_is_literal_by_default() {
+            let context = ParseContext::default();
+            let fields: Vec<_> = RowSpanIterator::new(&context, br"a\,b").collect();
+
+            assert_eq!(fields, vec![&br"a\"[..], b"b"]);
+        }
+
+        #[test]
+        fn is_quoted() {
+            let context = ParseContext::default();
+            let mut iterator = RowSpanIterator::new(&context, b"a,\"b\",c");
+
+            iterator.next();
+            assert!(!iterator.is_quoted());
+            iterator.next();
+            assert!(iterator.is_quoted());
+            iterator.next();
+            assert!(!iterator.is_quoted());
+        }
+
+        #[test]
+        fn keeps_initial_space_by_default() {
+            let context = ParseContext::default();
+            let mut iterator = RowSpanIterator::new(&context, b"a, b,c");
+
+            assert_eq!(Some(&b"a"[..]), iterator.next().as_deref());
+            assert_eq!(Some(&b" b"[..]), iterator.next().as_deref());
+            assert_eq!(Some(&b"c"[..]), iterator.next().as_deref());
+        }
+
+        #[test]
+        fn skip_initial_space() {
+            let context = ParseContext::builder()
+                .skip_initial_space(true)
+                .build()
+                .unwrap();
+            let mut iterator = RowSpanIterator::new(&context, b"a, b,c");
+
+            assert_eq!(Some(&b"a"[..]), iterator.next().as_deref());
+            assert_eq!(Some(&b"b"[..]), iterator.next().as_deref());
+            assert_eq!(Some(&b"c"[..]), iterator.next().as_deref());
+        }
+
+        #[test]
+        fn ends_after_last_field() {
+            let context = ParseContext::default();
+            let fields: Vec<_> = RowSpanIterator::new(&context, b"a,b").collect();
+
+            assert_eq!(fields, vec![&b"a"[..], &b"b"[..]]);
+        }
+
+        fn split(row: &[u8]) -> Vec<Vec<u8>> {
+            let context = ParseContext::default();
+            RowSpanIterator::new(&context, row)
+                .map(|f| f.into_owned())
+                .collect()
+        }
+
+        #[test]
+        fn quoted_field_keeps_delimiter() {
+            assert_eq!(vec![b"a,b".to_vec(), b"c".to_vec()], split(b"\"a,b\",c"));
+        }
+
+        #[test]
+        fn quoted_field_with_escaped_quotes_only() {
+            assert_eq!(vec![b"\"x\"".to_vec()], split(b"\"\"\"x\"\"\""));
+        }
+
+        #[test]
+        fn quoted_field_with_escaped_quote_inside() {
+            assert_eq!(vec![b"a\"b".to_vec()], split(b"\"a\"\"b\""));
+        }
+
+        #[test]
+        fn quoted_field_with_single_escaped_quote() {
+            assert_eq!(vec![b"\"".to_vec(), b"z".to_vec()], split(b"\"\"\"\",z"));
+        }
+
+        #[test]
+        fn quoted_field_with_trailing_escaped_quote() {
+            assert_eq!(vec![b"ab\"".to_vec()], split(b"\"ab\"\"\""));
+        }
+
+        #[test]
+        fn quoted_field_without_escapes_is_borrowed() {
+            let context = ParseContext::default();
+            let field = RowSpanIterator::new(&context, b"\"abc\"").next().unwrap();
+
+            assert!(matches!(field, std::borrow::Cow::Borrowed(b"abc")));
+        }
+
+        #[test]
+        fn quotes_disabled_keeps_quotes() {
+            let context = ParseContext::builder().quote(None).build().unwrap();
+            let fields: Vec<_> = RowSpanIterator::new(&context, b"\"a,b\"").collect();
+
+            assert_eq!(fields, vec![&b"\"a"[..], &b"b\""[..]]);
+        }
+    }
+
+    mod checked_row_parser {
+        use crate::parser::{CheckedRowParser, FieldValue, ParseContext, ParseError, RowParser};
+
+        #[test]
+        fn parse_reports_invalid_fields() {
+            let row = b"hello,,\xfc,1.5";
+
+            let context = ParseContext::default();
+
+            let result = CheckedRowParser::parse(row, &context);
+            let fields = result.fields();
+
+            assert_eq!(4, fields.len());
+            assert_eq!(&Ok(Some(FieldValue::String("hello".into()))), &fields[0]);
+            assert_eq!(&Ok(None), &fields[1]);
+            assert!(matches!(
+                fields[2],
+                Err(ParseError::InvalidField { column: 2, .. })
+            ));
+            assert_eq!(&Ok(Some(FieldValue::Float(1.5))), &fields[3]);
+            assert_eq!(vec![2], result.invalid_columns());
+        }
+    }
+
+    mod bytes_parser {
+        use crate::parser::{BytesParser, FieldParser, TrimmedBytesParser};
+
+        #[test]
+        fn parse_returns_bytes_verbatim() {
+            assert_eq!(
+                b" \xff\x00 ".to_vec(),
+                BytesParser::parse(b" \xff\x00 ").unwrap()
+            );
+        }
+
+        #[test]
+        fn trimmed_parse_removes_whitespace() {
+            assert_eq!(
+                b"\xff\x00".to_vec(),
+                TrimmedBytesParser::parse(b" \xff\x00 ").unwrap()
+            );
+        }
+    }
+
+    mod trim_chars {
+        use crate::{
+            parser::{FieldParser, FieldValue, FloatParser, ParseContext, StringParser},
+            CsvReader, DefaultSchema,
+        };
+
+        fn stars() -> ParseContext {
+            ParseContext::builder()
+                .trim_chars(Some(b"*_"))
+                .build()
+                .unwrap()
+        }
+
+        #[test]
+        fn parse_removes_trim_chars() {
+            let result = StringParser::parse_with_context(b"*_value*", &stars());
+            assert_eq!("value", result.unwrap());
+
+            let result = FloatParser::<f64>::parse_with_context(b"**1.5*", &stars());
+            assert_eq!(1.5, result.unwrap());
+        }
+
+        #[test]
+        fn strings_are_not_trimmed_by_default() {
+            let result = StringParser::parse_with_context(b" *value* ", &ParseContext::default());
+            assert_eq!(" *value* ", result.unwrap());
+        }
+
+        #[test]
+        fn read_with_trim_chars() {
+            let reader = CsvReader::<DefaultSchema>::default().with_context(stars());
+            let csv = b"name,height\n**foo*_,*1.5*\n";
+
+            let rows = reader.read(csv).unwrap();
+
+            assert_eq!(Some(FieldValue::String("foo".into())), rows[0].fields()[0]);
+            assert_eq!(Some(FieldValue::Float(1.5)), rows[0].fields()[1]);
+            assert_eq!(vec![vec!["foo", "1.5"]], reader.read_raw_strings(csv));
+        }
+    }
+
+    mod ascii_trim {
+        use crate::{
+            parser::{
+                ConfiguredFieldParser, CurrencyParser, FieldParser, IntParser, LookupParser,
+                ParseContext, Unmapped,
+            },
+            CsvReader, DefaultSchema,
+        };
+
+        fn ascii() -> ParseContext {
+            ParseContext::builder().ascii_trim(true).build().unwrap()
+        }
+
+        #[test]
+        fn non_breaking_space_is_kept() {
+            let csv = " \u{a0}foo\u{a0} ,1\n".as_bytes();
+            let reader = CsvReader::<DefaultSchema>::default().has_headers(false);
+
+            assert_eq!(vec![vec!["foo", "1"]], reader.read_raw_strings(csv));
+            assert_eq!(
+                vec![vec!["\u{a0}foo\u{a0}", "1"]],
+                reader
+                    .with_context(ascii())
+                    .has_headers(false)
+                    .read_raw_strings(csv)
+            );
+        }
+
+        #[test]
+        fn numbers_with_non_breaking_space() {
+            let field = "\t42\u{a0}".as_bytes();
+
+            let unicode = IntParser::<i32>::parse_with_context(field, &ParseContext::default());
+            assert_eq!(42, unicode.unwrap());
+            assert!(IntParser::<i32>::parse_with_context(field, &ascii()).is_err());
+            assert_eq!(
+                42,
+                IntParser::<i32>::parse_with_context(b"\t42 ", &ascii()).unwrap()
+            );
+        }
+
+        #[test]
+        fn currencies_and_lookup_keys_with_non_breaking_space() {
+            let currency = CurrencyParser::new("$");
+            let field = "\u{a0}$5 ".as_bytes();
+            assert_eq!(
+                5.0,
+                currency
+                    .parse_field(field, &ParseContext::default())
+                    .unwrap()
+            );
+            assert!(currency.parse_field(field, &ascii()).is_err());
+
+            let lookup = LookupParser::from_pairs([("US", 1.0)], Unmapped::Error);
+            let field = "US\u{a0}".as_bytes();
+            assert_eq!(
+                1.0,
+                lookup.parse_field(field, &ParseContext::default()).unwrap()
+            );
+            assert!(lookup.parse_field(field, &ascii()).is_err());
+        }
+    }
+
+    mod string_parser {
+        use crate::parser::{FieldParser, ParseContext, RowSpanIterator, StringParser};
+
+        /// Parses each field of the row, whose escaped quotes are collapsed by the
+        /// [RowSpanIterator].
+        fn parse_fields(row: &[u8]) -> Vec<String> {
+            let context = ParseContext::default();
+
+            RowSpanIterator::new(&context, row)
+                .map(|field| StringParser::parse_with_context(&field, &context).unwrap())
+                .collect()
+        }
+
+        #[test]
+        fn parse_quoted_field_collapses_doubled_quotes() {
+            assert_eq!(
+                vec!["she said \"hi\"", "x"],
+                parse_fields(br#""she said ""hi""",x"#)
+            );
+        }
+
+        #[test]
+        fn parse_quoted_field_of_only_an_escaped_quote() {
+            assert_eq!(vec!["\"", ""], parse_fields(b"\"\"\"\","));
+        }
+
+        #[test]
+        fn parse_quoted_field_with_escaped_quote_before_closing_quote() {
+            assert_eq!(
+                vec!["end\"", "a\"\"b"],
+                parse_fields(br#""end""","a""""b""#)
+            );
+        }
+
+        #[test]
+        fn parse_unquoted_field_keeps_doubled_quotes() {
+            assert_eq!(vec!["a\"\"b"], parse_fields(br#"a""b"#));
+        }
 
         #[test]
         fn parse_when_valid_string_returns_ok() {
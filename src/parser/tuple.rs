@@ -0,0 +1,77 @@
+use super::{
+    is_null, try_parse_with_context, IntoFieldParser, ParseContext, RowSpan, RowSpanIterator,
+};
+
+/// A tuple of types with a [super::FieldParser], such as `(i64, String, f64)`, that a row
+/// can be read into without defining a schema, see [crate::CsvReader::read_tuples].
+///
+/// Implemented for tuples of up to 12 elements.
+pub trait TupleRow {
+    /// The tuple of the parsed fields, `None` when a field is empty or cannot be parsed.
+    type Output;
+
+    /// Parses the fields of the row in order. Missing fields are empty, see
+    /// [super::is_null].
+    fn parse_tuple(row: &RowSpan, context: &ParseContext) -> Self::Output;
+}
+
+macro_rules! impl_tuple_row {
+    ($($type:ident),+) => {
+        impl<$($type: IntoFieldParser<$type>),+> TupleRow for ($($type,)+) {
+            type Output = ($(Option<$type>,)+);
+
+            fn parse_tuple(row: &RowSpan, context: &ParseContext) -> Self::Output {
+                let mut iterator = RowSpanIterator::new(context, row);
+                ($({
+                    let span = iterator.next().unwrap_or_default();
+                    if is_null(&span, iterator.is_quoted(), context) {
+                        None
+                    } else {
+                        try_parse_with_context::<$type>(&span, context)
+                    }
+                },)+)
+            }
+        }
+    };
+}
+
+impl_tuple_row!(A);
+impl_tuple_row!(A, B);
+impl_tuple_row!(A, B, C);
+impl_tuple_row!(A, B, C, D);
+impl_tuple_row!(A, B, C, D, E);
+impl_tuple_row!(A, B, C, D, E, F);
+impl_tuple_row!(A, B, C, D, E, F, G);
+impl_tuple_row!(A, B, C, D, E, F, G, H);
+impl_tuple_row!(A, B, C, D, E, F, G, H, I);
+impl_tuple_row!(A, B, C, D, E, F, G, H, I, J);
+impl_tuple_row!(A, B, C, D, E, F, G, H, I, J, K);
+impl_tuple_row!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod test {
+    use crate::{CsvReader, DefaultSchema};
+
+    #[test]
+    fn read_three_columns_into_tuples() {
+        let csv = b"id,name,height\n1,foo,1.5\nx,bar,\n3\n";
+
+        let rows = CsvReader::<DefaultSchema>::default().read_tuples::<(i64, String, f64)>(csv);
+
+        assert_eq!(
+            vec![
+                (Some(1), Some("foo".to_string()), Some(1.5)),
+                (None, Some("bar".to_string()), None),
+                (Some(3), None, None),
+            ],
+            rows
+        );
+    }
+
+    #[test]
+    fn read_single_column_into_tuples() {
+        let rows = CsvReader::<DefaultSchema>::default().read_tuples::<(u8,)>(b"n\n7\n");
+
+        assert_eq!(vec![(Some(7),)], rows);
+    }
+}
@@ -0,0 +1,70 @@
+use std::error::Error;
+
+use ::uuid::Uuid;
+
+use super::{FieldParser, IntoFieldParser, RowSpan};
+
+/// Parses a UUID in its hyphenated form, such as `67e55044-10b1-426f-9247-bb680e5fe0c8`,
+/// or in its simple form, without hyphens. Whitespace around the value is ignored.
+pub struct UuidParser;
+
+impl FieldParser<Uuid> for UuidParser {
+    fn parse(span: &RowSpan) -> Result<Uuid, Box<dyn Error>> {
+        let s = std::str::from_utf8(span)?.trim();
+
+        match Uuid::parse_str(s) {
+            Ok(v) => Ok(v),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl IntoFieldParser<Uuid> for Uuid {
+    type Parser = UuidParser;
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::{
+        parser::{FieldParser, UuidParser},
+        schema, CsvReader,
+    };
+
+    schema!(User, id:Uuid, name:String);
+
+    const ID: Uuid = Uuid::from_u128(0x67e55044_10b1_426f_9247_bb680e5fe0c8);
+
+    #[test]
+    fn parse_hyphenated_uuid() {
+        assert_eq!(
+            ID,
+            UuidParser::parse(b" 67e55044-10b1-426f-9247-bb680e5fe0c8 ").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_simple_uuid() {
+        assert_eq!(
+            ID,
+            UuidParser::parse(b"67e5504410b1426f9247bb680e5fe0c8").unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_invalid_uuid_returns_err() {
+        assert!(UuidParser::parse(b"67e55044-10b1-426f").is_err());
+        assert!(UuidParser::parse(b"not a uuid").is_err());
+    }
+
+    #[test]
+    fn read_uuid_column() {
+        let csv = b"id,name\n67e55044-10b1-426f-9247-bb680e5fe0c8,foo\nbar,baz\n";
+
+        let rows = CsvReader::<User>::default().read(csv).unwrap();
+
+        assert_eq!(Some(ID), rows[0].id);
+        assert_eq!(None, rows[1].id);
+    }
+}
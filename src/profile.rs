@@ -0,0 +1,124 @@
+use crate::parser::{ColumnType, FieldValue};
+
+/// Statistics about the values of a column, see [crate::CsvReader::profile].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ColumnProfile {
+    /// The type of the values of the column. A column of integers and floats is a
+    /// [ColumnType::Float] column, and a column whose values have other different types
+    /// is a [ColumnType::String] column. `None` if every value is empty.
+    pub column_type: Option<ColumnType>,
+    /// The number of empty values, including the rows without this column.
+    pub nulls: usize,
+    /// The number of numeric values.
+    pub count: usize,
+    /// The smallest numeric value, if any.
+    pub min: Option<f64>,
+    /// The largest numeric value, if any.
+    pub max: Option<f64>,
+    /// The sum of the numeric values.
+    pub sum: f64,
+}
+
+impl ColumnProfile {
+    /// The mean of the numeric values, if any.
+    pub fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    fn add(&mut self, value: &FieldValue) {
+        self.column_type = match self.column_type {
+            None => Some(value.column_type()),
+            Some(t) => Some(t.widen(value.column_type())),
+        };
+
+        let number = match value {
+            FieldValue::Float(v) => *v,
+            FieldValue::Int(v) => *v as f64,
+            FieldValue::String(_) | FieldValue::Bool(_) => return,
+        };
+
+        self.count += 1;
+        self.sum += number;
+        self.min = Some(self.min.map_or(number, |m| m.min(number)));
+        self.max = Some(self.max.map_or(number, |m| m.max(number)));
+    }
+}
+
+/// Computes the profile of each column, one row at a time.
+#[derive(Debug, Default)]
+pub(crate) struct Profiler {
+    rows: usize,
+    columns: Vec<ColumnProfile>,
+}
+
+impl Profiler {
+    pub(crate) fn add_row(&mut self, fields: &[Option<FieldValue>]) {
+        // A column first seen in this row was empty in the previous rows.
+        let rows = self.rows;
+        if self.columns.len() < fields.len() {
+            self.columns.resize_with(fields.len(), || ColumnProfile {
+                nulls: rows,
+                ..Default::default()
+            });
+        }
+
+        for (i, column) in self.columns.iter_mut().enumerate() {
+            match fields.get(i) {
+                Some(Some(value)) => column.add(value),
+                _ => column.nulls += 1,
+            }
+        }
+
+        self.rows += 1;
+    }
+
+    pub(crate) fn finish(self) -> Vec<ColumnProfile> {
+        self.columns
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parser::ColumnType, CsvReader, DefaultSchema};
+
+    #[test]
+    fn profile_mixed_type_columns() {
+        let csv = b"name,height,code\nfoo,1.5,a\nbar,,2\nbaz,2.5\n";
+
+        let profiles = CsvReader::<DefaultSchema>::default().profile(csv).unwrap();
+
+        assert_eq!(3, profiles.len());
+
+        assert_eq!(Some(ColumnType::String), profiles[0].column_type);
+        assert_eq!(0, profiles[0].nulls);
+        assert_eq!(0, profiles[0].count);
+        assert_eq!(None, profiles[0].mean());
+
+        let height = &profiles[1];
+        assert_eq!(Some(ColumnType::Float), height.column_type);
+        assert_eq!(1, height.nulls);
+        assert_eq!(2, height.count);
+        assert_eq!(Some(1.5), height.min);
+        assert_eq!(Some(2.5), height.max);
+        assert_eq!(4.0, height.sum);
+        assert_eq!(Some(2.0), height.mean());
+
+        let code = &profiles[2];
+        assert_eq!(Some(ColumnType::String), code.column_type);
+        assert_eq!(1, code.nulls);
+        assert_eq!(1, code.count);
+        assert_eq!(Some(2.0), code.min);
+    }
+
+    #[test]
+    fn profile_int_and_float_column_is_float() {
+        let csv = b"value,flag\n1,true\n2.5,1\n";
+
+        let profiles = CsvReader::<DefaultSchema>::default().profile(csv).unwrap();
+
+        assert_eq!(Some(ColumnType::Float), profiles[0].column_type);
+        assert_eq!(2, profiles[0].count);
+        assert_eq!(3.5, profiles[0].sum);
+        assert_eq!(Some(ColumnType::String), profiles[1].column_type);
+    }
+}
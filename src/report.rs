@@ -0,0 +1,245 @@
+use crate::{
+    header,
+    parser::{CheckedRowParser, ParseContext, ParseError, RowParser, RowSpanIterator},
+    CsvReader, DefaultSchema, RowIterator,
+};
+
+/// The kind of problem found while validating a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationErrorKind {
+    /// The field cannot be parsed, such as a field that is not valid UTF-8.
+    InvalidField,
+    /// A quoted field has data after its closing quote.
+    TrailingAfterQuote,
+    /// The row does not have as many fields as the header, or as the first row if
+    /// the document has no header.
+    WrongColumnCount,
+}
+
+impl ValidationErrorKind {
+    /// The name of the kind, in snake case.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValidationErrorKind::InvalidField => "invalid_field",
+            ValidationErrorKind::TrailingAfterQuote => "trailing_after_quote",
+            ValidationErrorKind::WrongColumnCount => "wrong_column_count",
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A problem found while validating a document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// The 1-based line of the row.
+    pub line: usize,
+    /// The 0-based column of the field, if the problem is about a single field.
+    pub column: Option<usize>,
+    pub kind: ValidationErrorKind,
+    /// The offending field, or the whole row if the problem is about the row.
+    pub value: String,
+}
+
+/// The problems found in a document by [crate::CsvReader::validate_report].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport {
+    /// The number of data rows, excluding the header if any.
+    pub rows: usize,
+    pub errors: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    /// Returns true if no problem was found.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The number of problems of this kind.
+    pub fn count(&self, kind: ValidationErrorKind) -> usize {
+        self.errors.iter().filter(|e| e.kind == kind).count()
+    }
+
+    /// Serializes the report, with the number of rows and of each kind of problem:
+    ///
+    /// ```json
+    /// {"rows":2,"counts":{"invalid_field":1,...},"errors":[{"line":3,"column":0,"kind":"invalid_field","value":"\u{fffd}"}]}
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> String {
+        use serde_json::{json, Map, Value};
+
+        let kinds = [
+            ValidationErrorKind::InvalidField,
+            ValidationErrorKind::TrailingAfterQuote,
+            ValidationErrorKind::WrongColumnCount,
+        ];
+        let counts: Map<String, Value> = kinds
+            .iter()
+            .map(|k| (k.name().to_string(), json!(self.count(*k))))
+            .collect();
+
+        let errors: Vec<Value> = self
+            .errors
+            .iter()
+            .map(|e| {
+                json!({
+                    "line": e.line,
+                    "column": e.column,
+                    "kind": e.kind.name(),
+                    "value": e.value,
+                })
+            })
+            .collect();
+
+        json!({
+            "rows": self.rows,
+            "counts": counts,
+            "errors": errors,
+        })
+        .to_string()
+    }
+}
+
+/// Validates every data row of the document against the header, or against the first
+/// row if the document has no header.
+pub(crate) fn validate(span: &[u8], context: &ParseContext) -> ValidationReport {
+    let mut iterator = RowIterator::new(span, context);
+    let mut report = ValidationReport::default();
+
+    // The header may have its own delimiter, and span several rows.
+    let expected = match RowIterator::new(span, context).next() {
+        Some(_) if context.has_header() => {
+            header::header_names(CsvReader::<DefaultSchema>::header(span, context), context).len()
+        }
+        Some(first) => RowSpanIterator::new(context, first).count(),
+        None => return report,
+    };
+
+    iterator.by_ref().take(context.header_rows()).for_each(drop);
+
+    while let Some(row) = iterator.next() {
+        let line = iterator.line();
+        let raw = || String::from_utf8_lossy(row).into_owned();
+        report.rows += 1;
+
+        let mut fields = RowSpanIterator::new(context, row);
+        let count = fields.by_ref().count();
+
+        if count != expected {
+            report.errors.push(ValidationError {
+                line,
+                column: None,
+                kind: ValidationErrorKind::WrongColumnCount,
+                value: raw(),
+            });
+        }
+
+        if fields.has_trailing_after_quote() {
+            report.errors.push(ValidationError {
+                line,
+                column: None,
+                kind: ValidationErrorKind::TrailingAfterQuote,
+                value: raw(),
+            });
+        }
+
+        let checked = CheckedRowParser::parse(row, context);
+        for (field, result) in RowSpanIterator::new(context, row).zip(checked.fields()) {
+            if let Err(ParseError::InvalidField { column, .. }) = result {
+                report.errors.push(ValidationError {
+                    line,
+                    column: Some(*column),
+                    kind: ValidationErrorKind::InvalidField,
+                    value: String::from_utf8_lossy(&field).into_owned(),
+                });
+            }
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        parser::ParseContext,
+        report::{ValidationError, ValidationErrorKind},
+        CsvReader, DefaultSchema,
+    };
+
+    #[test]
+    fn valid_document_returns_empty_report() {
+        let report = CsvReader::<DefaultSchema>::default().validate_report(b"a,b\n1,2\n3,4\n");
+
+        assert!(report.is_ok());
+        assert_eq!(2, report.rows);
+    }
+
+    #[test]
+    fn reports_each_problem_with_its_line() {
+        let csv = b"a,b\n1\n\xff,2\n\"x\"y,3\n";
+
+        let report = CsvReader::<DefaultSchema>::default().validate_report(csv);
+
+        assert_eq!(3, report.rows);
+        assert_eq!(
+            vec![
+                ValidationError {
+                    line: 2,
+                    column: None,
+                    kind: ValidationErrorKind::WrongColumnCount,
+                    value: "1".into(),
+                },
+                ValidationError {
+                    line: 3,
+                    column: Some(0),
+                    kind: ValidationErrorKind::InvalidField,
+                    value: "\u{fffd}".into(),
+                },
+                ValidationError {
+                    line: 4,
+                    column: None,
+                    kind: ValidationErrorKind::TrailingAfterQuote,
+                    value: "\"x\"y,3".into(),
+                },
+            ],
+            report.errors
+        );
+        assert_eq!(1, report.count(ValidationErrorKind::InvalidField));
+    }
+
+    #[test]
+    fn header_with_its_own_delimiter() {
+        let context = ParseContext::builder()
+            .header_delimiter(Some(b'\t'))
+            .build()
+            .unwrap();
+
+        let report = CsvReader::<DefaultSchema>::default()
+            .with_context(context)
+            .validate_report(b"a\tb\n1,2\n3\n");
+
+        assert_eq!(1, report.count(ValidationErrorKind::WrongColumnCount));
+        assert_eq!(3, report.errors[0].line);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn to_json() {
+        let report = CsvReader::<DefaultSchema>::default().validate_report(b"a,b\n1\n");
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+
+        assert_eq!(1, json["rows"]);
+        assert_eq!(1, json["counts"]["wrong_column_count"]);
+        assert_eq!(0, json["counts"]["invalid_field"]);
+        assert_eq!(2, json["errors"][0]["line"]);
+        assert_eq!(serde_json::Value::Null, json["errors"][0]["column"]);
+        assert_eq!("wrong_column_count", json["errors"][0]["kind"]);
+    }
+}
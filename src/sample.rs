@@ -0,0 +1,90 @@
+/// A SplitMix64 pseudo-random number generator, good enough for sampling and
+/// deterministic for a given seed.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..=max`.
+    fn below_or_equal(&mut self, max: usize) -> usize {
+        (self.next() % (max as u64 + 1)) as usize
+    }
+}
+
+/// Selects `n` items with equal probability using reservoir sampling, so that memory
+/// stays O(n) regardless of the number of items. The items are returned in their
+/// original order.
+pub(crate) fn reservoir<T>(items: impl Iterator<Item = T>, n: usize, seed: u64) -> Vec<T> {
+    let mut rng = SplitMix64(seed);
+    // `n` may be far more than the number of items, such as `usize::MAX` to keep them all.
+    let mut reservoir: Vec<(usize, T)> = Vec::new();
+
+    if n == 0 {
+        return Vec::new();
+    }
+
+    for (i, item) in items.enumerate() {
+        if i < n {
+            reservoir.push((i, item));
+        } else {
+            let j = rng.below_or_equal(i);
+            if j < n {
+                reservoir[j] = (i, item);
+            }
+        }
+    }
+
+    reservoir.sort_by_key(|(i, _)| *i);
+    reservoir.into_iter().map(|(_, item)| item).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::sample::reservoir;
+
+    #[test]
+    fn returns_all_items_when_fewer_than_n() {
+        assert_eq!(vec![1, 2, 3], reservoir([1, 2, 3].into_iter(), 5, 42));
+    }
+
+    #[test]
+    fn huge_n_returns_all_items() {
+        assert_eq!(
+            vec![1, 2, 3],
+            reservoir([1, 2, 3].into_iter(), usize::MAX, 42)
+        );
+    }
+
+    #[test]
+    fn returns_n_items_in_original_order() {
+        let sample = reservoir(0..1000, 10, 42);
+
+        assert_eq!(10, sample.len());
+        assert!(sample.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn same_seed_returns_same_sample() {
+        assert_eq!(reservoir(0..1000, 10, 7), reservoir(0..1000, 10, 7));
+        assert_ne!(reservoir(0..1000, 10, 7), reservoir(0..1000, 10, 8));
+    }
+
+    #[test]
+    fn every_item_can_be_selected() {
+        let mut counts = [0; 10];
+        for seed in 0..2000 {
+            for i in reservoir(0..10, 1, seed) {
+                counts[i] += 1;
+            }
+        }
+
+        // Each item is expected ~200 times.
+        assert!(counts.iter().all(|c| *c > 100), "{:?}", counts);
+    }
+}
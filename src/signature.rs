@@ -0,0 +1,128 @@
+use crate::{
+    parser::{ColumnType, DefaultRowParser, ParseContext, RowParser},
+    RowIterator,
+};
+
+/// The number of data rows used to infer the column types.
+pub const SIGNATURE_SAMPLE_ROWS: usize = 100;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// A FNV-1a hasher. Unlike [std::collections::hash_map::DefaultHasher], its output
+/// is guaranteed to be the same across Rust versions and platforms.
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Self(FNV_OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 ^= *b as u64;
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+}
+
+fn type_tag(column_type: Option<ColumnType>) -> u8 {
+    match column_type {
+        None => 0,
+        Some(ColumnType::Float) => 1,
+        Some(ColumnType::String) => 2,
+        Some(ColumnType::Int) => 3,
+        Some(ColumnType::Bool) => 4,
+    }
+}
+
+/// Computes a fingerprint of the structure of the CSV document: the header row and
+/// the column types inferred from the first [SIGNATURE_SAMPLE_ROWS] rows.
+///
+/// Two documents with the same signature have the same header and the same inferred
+/// column types, which can be used to decide whether a previously processed file must
+/// be processed again. The signature is stable across runs and platforms.
+pub fn schema_signature(span: &[u8]) -> u64 {
+    let span = &*crate::decode(span);
+    let default = ParseContext::default();
+    let context = default.for_document(span);
+    let mut rows = RowIterator::new(span, &context);
+    let mut hasher = Fnv1a::new();
+
+    let header = rows.next().unwrap_or_default();
+    hasher.write(header);
+
+    let mut types: Vec<Option<ColumnType>> = Vec::new();
+
+    for row in rows.take(SIGNATURE_SAMPLE_ROWS) {
+        let fields = DefaultRowParser::parse(row, &context).fields;
+
+        if types.len() < fields.len() {
+            types.resize(fields.len(), None);
+        }
+
+        for (i, field) in fields.iter().enumerate() {
+            if let Some(value) = field {
+                types[i] = match types[i] {
+                    None => Some(value.column_type()),
+                    Some(t) => Some(t.widen(value.column_type())),
+                };
+            }
+        }
+    }
+
+    // Separates the header from the types, so that they cannot be confused.
+    hasher.write(&[0xFF]);
+    hasher.write(&(types.len() as u64).to_le_bytes());
+    for t in types {
+        hasher.write(&[type_tag(t)]);
+    }
+
+    hasher.0
+}
+
+#[cfg(test)]
+mod test {
+    use crate::schema_signature;
+
+    #[test]
+    fn same_structure_returns_same_signature() {
+        let a = schema_signature(b"name,height\nfoo,0.3\nbar,2\n");
+        let b = schema_signature(b"name,height\nbaz,10\nqux,1.5\n");
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_header_returns_different_signature() {
+        let a = schema_signature(b"name,height\nfoo,0.3\n");
+        let b = schema_signature(b"name,weight\nfoo,0.3\n");
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn different_types_returns_different_signature() {
+        let a = schema_signature(b"name,height\nfoo,0.3\n");
+        let b = schema_signature(b"name,height\nfoo,tall\n");
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn mixed_numbers_differ_from_text() {
+        let a = schema_signature(b"a\n1\n2.5\n");
+        let b = schema_signature(b"a\nx\ny\n");
+
+        assert_ne!(a, b);
+        assert_eq!(a, schema_signature(b"a\n0.5\n3.5\n"));
+    }
+
+    #[test]
+    fn signature_is_stable() {
+        let signature = schema_signature(b"a,b\n1,x\n");
+
+        assert_eq!(signature, schema_signature(b"a,b\n1,x\n"));
+        assert_ne!(0, signature);
+    }
+}
@@ -0,0 +1,206 @@
+use std::{borrow::Cow, error::Error};
+
+use crate::{
+    parser::{ColumnType, ParseContext, RowSpan, RowSpanIterator},
+    DefaultSchema,
+};
+
+/// The columns expected in a document, defined at runtime, such as from the configuration
+/// of a pipeline. See [crate::CsvReader::read_with_spec].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Spec {
+    /// The name of each column, the type of its values, and whether it is required.
+    ///
+    /// A required column must be in the header and have a value in every row. An optional
+    /// column may be missing from the header, and its values may be empty.
+    pub columns: Vec<(String, ColumnType, bool)>,
+}
+
+/// A problem found while reading a document with a [Spec].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpecError {
+    /// The required column is not in the header.
+    MissingColumn(String),
+    /// The required column is empty on the line (1-based).
+    MissingValue { line: usize, column: String },
+    /// The value of the column on the line (1-based) is not of the expected type.
+    InvalidValue {
+        line: usize,
+        column: String,
+        expected: ColumnType,
+        value: String,
+    },
+}
+
+impl std::fmt::Display for SpecError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SpecError::MissingColumn(column) => {
+                write!(f, "Spec error: missing required column '{}'", column)
+            }
+            SpecError::MissingValue { line, column } => write!(
+                f,
+                "Spec error: missing value of required column '{}' on line {}",
+                column, line
+            ),
+            SpecError::InvalidValue {
+                line,
+                column,
+                expected,
+                value,
+            } => write!(
+                f,
+                "Spec error: '{}' in column '{}' on line {} is not a {:?}",
+                value, column, line, expected
+            ),
+        }
+    }
+}
+
+impl Error for SpecError {}
+
+impl Spec {
+    /// Returns the position in the header of each column of the spec, if any.
+    pub(crate) fn resolve(&self, names: &[String]) -> Result<Vec<Option<usize>>, Vec<SpecError>> {
+        let mut errors = Vec::new();
+        let positions = self
+            .columns
+            .iter()
+            .map(|(name, _, required)| {
+                let position = names.iter().position(|n| n == name);
+                if position.is_none() && *required {
+                    errors.push(SpecError::MissingColumn(name.clone()));
+                }
+                position
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(positions)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Parses the row found on the line, with a field for each column of the spec, in the
+    /// order of the spec. The problems found are appended to `errors`.
+    pub(crate) fn parse_row(
+        &self,
+        positions: &[Option<usize>],
+        line: usize,
+        row: &RowSpan,
+        context: &ParseContext,
+        errors: &mut Vec<SpecError>,
+    ) -> DefaultSchema {
+        let fields: Vec<Cow<[u8]>> = RowSpanIterator::new(context, row).collect();
+
+        let values = self
+            .columns
+            .iter()
+            .zip(positions)
+            .map(|((name, column_type, required), position)| {
+                let span = position
+                    .and_then(|p| fields.get(p))
+                    .filter(|span| !context.is_empty_field(span));
+
+                let Some(span) = span else {
+                    if *required {
+                        errors.push(SpecError::MissingValue {
+                            line,
+                            column: name.clone(),
+                        });
+                    }
+                    return None;
+                };
+
+                match column_type.parse(span, context) {
+                    Ok(value) => Some(value),
+                    Err(_) => {
+                        errors.push(SpecError::InvalidValue {
+                            line,
+                            column: name.clone(),
+                            expected: *column_type,
+                            value: String::from_utf8_lossy(span).into_owned(),
+                        });
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        DefaultSchema::new(values)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        parser::{ColumnType, FieldValue},
+        CsvReader, DefaultSchema, Spec, SpecError,
+    };
+
+    fn spec() -> Spec {
+        Spec {
+            columns: vec![
+                ("id".into(), ColumnType::Int, true),
+                ("name".into(), ColumnType::String, true),
+                ("score".into(), ColumnType::Float, false),
+                ("comment".into(), ColumnType::String, false),
+            ],
+        }
+    }
+
+    #[test]
+    fn reads_columns_in_spec_order() {
+        let csv = b"name,score,id\nfoo,1.5,1\nbar,,2\n";
+
+        let rows = CsvReader::<DefaultSchema>::default()
+            .read_with_spec(csv, &spec())
+            .unwrap();
+
+        assert_eq!(
+            &vec![
+                Some(FieldValue::Int(1)),
+                Some(FieldValue::String("foo".into())),
+                Some(FieldValue::Float(1.5)),
+                None
+            ],
+            rows[0].fields()
+        );
+        assert_eq!(None, rows[1].fields()[2]);
+    }
+
+    #[test]
+    fn missing_required_column_returns_err() {
+        let errors = CsvReader::<DefaultSchema>::default()
+            .read_with_spec(b"id,score\n1,2\n", &spec())
+            .unwrap_err();
+
+        assert_eq!(vec![SpecError::MissingColumn("name".into())], errors);
+    }
+
+    #[test]
+    fn reports_missing_and_invalid_values() {
+        let csv = b"id,name,score\n1,foo,high\n,bar,2\n";
+
+        let errors = CsvReader::<DefaultSchema>::default()
+            .read_with_spec(csv, &spec())
+            .unwrap_err();
+
+        assert_eq!(
+            vec![
+                SpecError::InvalidValue {
+                    line: 2,
+                    column: "score".into(),
+                    expected: ColumnType::Float,
+                    value: "high".into()
+                },
+                SpecError::MissingValue {
+                    line: 3,
+                    column: "id".into()
+                },
+            ],
+            errors
+        );
+    }
+}
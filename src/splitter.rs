@@ -0,0 +1,180 @@
+use crate::parser::ParseContext;
+
+/// Finds the boundaries of the records of a document, so that [crate::RowIterator] can
+/// read formats whose rows are not simply separated by a terminator character.
+pub trait RecordSplitter {
+    /// Returns the record that starts at `offset`, without its terminator, and the offset
+    /// of the next record, or `None` if there is no record left.
+    fn next_record<'a>(&mut self, data: &'a [u8], offset: usize) -> Option<(&'a [u8], usize)>;
+}
+
+/// Splits records on a terminator character, such as `\n`. The last record may have no
+/// terminator, as many documents omit the final newline.
+///
+/// With quotes, as created by [TerminatorSplitter::quoted], a terminator inside a quoted
+/// field is part of the record, following RFC 4180.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminatorSplitter {
+    terminator: u8,
+    quote: Option<u8>,
+    delimiter: u8,
+    skip_initial_space: bool,
+    quote_anywhere: bool,
+}
+
+impl TerminatorSplitter {
+    /// Splits on every terminator, even inside quoted fields.
+    pub fn new(terminator: u8) -> Self {
+        Self {
+            terminator,
+            quote: None,
+            delimiter: crate::COMMA,
+            skip_initial_space: false,
+            quote_anywhere: false,
+        }
+    }
+
+    /// Splits on the terminators outside the quoted fields, found with the quote and the
+    /// delimiter of the context.
+    pub fn quoted(terminator: u8, context: &ParseContext) -> Self {
+        Self {
+            terminator,
+            quote: context.quote(),
+            delimiter: context.delimiter(),
+            skip_initial_space: context.skip_initial_space(),
+            quote_anywhere: context.quote_anywhere(),
+        }
+    }
+
+    /// Returns the end of the record starting at `rest[0]`, skipping the terminators
+    /// inside quoted fields, or `rest.len()` if the record has no terminator, such as
+    /// when a quoted field is never closed.
+    fn quoted_record_end(&self, rest: &[u8], quote: u8) -> usize {
+        let mut field_start = true;
+        let mut in_quotes = false;
+        let mut i = 0;
+
+        while i < rest.len() {
+            let c = rest[i];
+            if in_quotes {
+                if c == quote {
+                    // A doubled quote is an escaped quote, not the closing quote.
+                    if rest.get(i + 1) == Some(&quote) {
+                        i += 1;
+                    } else {
+                        in_quotes = false;
+                    }
+                }
+            } else if c == self.terminator {
+                return i;
+            } else if c == self.delimiter {
+                field_start = true;
+            } else if c == quote && (field_start || self.quote_anywhere) {
+                in_quotes = true;
+                field_start = false;
+            } else if !(c == b' ' && field_start && self.skip_initial_space) {
+                field_start = false;
+            }
+            i += 1;
+        }
+
+        rest.len()
+    }
+}
+
+impl RecordSplitter for TerminatorSplitter {
+    fn next_record<'a>(&mut self, data: &'a [u8], offset: usize) -> Option<(&'a [u8], usize)> {
+        let rest = data.get(offset..)?;
+        let found = memchr::memchr(self.terminator, rest);
+
+        let index = match self.quote {
+            Some(quote)
+                if memchr::memchr(quote, &rest[..found.unwrap_or(rest.len())]).is_some() =>
+            {
+                self.quoted_record_end(rest, quote)
+            }
+            _ => match found {
+                Some(index) => index,
+                None if rest.is_empty() => return None,
+                None => rest.len(),
+            },
+        };
+
+        Some((&rest[..index], (offset + index + 1).min(data.len())))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        parser::ParseContext,
+        splitter::{RecordSplitter, TerminatorSplitter},
+        RowIterator,
+    };
+
+    /// Splits records on `||`.
+    struct DoublePipeSplitter;
+
+    impl RecordSplitter for DoublePipeSplitter {
+        fn next_record<'a>(&mut self, data: &'a [u8], offset: usize) -> Option<(&'a [u8], usize)> {
+            let rest = data.get(offset..)?;
+            let index = memchr::memmem::find(rest, b"||")?;
+
+            Some((&rest[..index], offset + index + 2))
+        }
+    }
+
+    #[test]
+    fn terminator_splitter_returns_records_and_next_offsets() {
+        let mut splitter = TerminatorSplitter::new(b'\n');
+        let data = b"a,b\nc,d\ne";
+
+        assert_eq!(Some((&b"a,b"[..], 4)), splitter.next_record(data, 0));
+        assert_eq!(Some((&b"c,d"[..], 8)), splitter.next_record(data, 4));
+        assert_eq!(Some((&b"e"[..], 9)), splitter.next_record(data, 8));
+        assert_eq!(None, splitter.next_record(data, 9));
+        assert_eq!(None, splitter.next_record(data, 20));
+    }
+
+    #[test]
+    fn quoted_splitter_keeps_terminators_inside_quotes() {
+        let context = ParseContext::default();
+        let mut splitter = TerminatorSplitter::quoted(b'\n', &context);
+        let data = b"a,\"b\nc\",d\n\"e\"\"\n\",f\ng\"h,i\n";
+
+        assert_eq!(
+            Some((&b"a,\"b\nc\",d"[..], 10)),
+            splitter.next_record(data, 0)
+        );
+        assert_eq!(
+            Some((&b"\"e\"\"\n\",f"[..], 19)),
+            splitter.next_record(data, 10)
+        );
+        // A quote inside an unquoted field does not start a quoted field.
+        assert_eq!(Some((&b"g\"h,i"[..], 25)), splitter.next_record(data, 19));
+        assert_eq!(None, splitter.next_record(data, 25));
+    }
+
+    #[test]
+    fn quoted_splitter_unterminated_quote_extends_to_the_end() {
+        let context = ParseContext::default();
+        let mut splitter = TerminatorSplitter::quoted(b'\n', &context);
+        let data = b"a,\"b\nc,d\n";
+
+        assert_eq!(Some((&data[..], 9)), splitter.next_record(data, 0));
+        assert_eq!(None, splitter.next_record(data, 9));
+    }
+
+    #[test]
+    fn row_iterator_with_custom_splitter() {
+        let context = ParseContext::default();
+        let data = b"a|b,c||d\ne,f||";
+
+        let mut iterator = RowIterator::with_splitter(data, &context, DoublePipeSplitter);
+
+        assert_eq!(Some(&b"a|b,c"[..]), iterator.next());
+        assert_eq!(Some(&b"d\ne,f"[..]), iterator.next());
+        assert_eq!(2, iterator.line());
+        assert_eq!(None, iterator.next());
+    }
+}
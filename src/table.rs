@@ -0,0 +1,120 @@
+use std::fmt::{Display, Formatter};
+
+use crate::{to_string_table, DefaultSchema};
+
+/// A table of strings, displayed with aligned and bordered columns:
+///
+/// ```text
+/// +------+--------+
+/// | name | height |
+/// +------+--------+
+/// | foo  | 0.5    |
+/// +------+--------+
+/// ```
+pub struct Table {
+    headers: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        Self { headers, rows }
+    }
+
+    /// Builds the table of the rows, where each [crate::parser::FieldValue] is stringified.
+    pub fn from_rows(headers: &[String], rows: &[DefaultSchema]) -> Self {
+        Self::new(headers.to_vec(), to_string_table(rows))
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        let mut widths: Vec<usize> = Vec::new();
+
+        for row in std::iter::once(&self.headers).chain(self.rows.iter()) {
+            if widths.len() < row.len() {
+                widths.resize(row.len(), 0);
+            }
+            for (i, cell) in row.iter().enumerate() {
+                widths[i] = widths[i].max(cell.chars().count());
+            }
+        }
+
+        widths
+    }
+}
+
+fn write_border(f: &mut Formatter<'_>, widths: &[usize]) -> std::fmt::Result {
+    for width in widths {
+        write!(f, "+{}", "-".repeat(width + 2))?;
+    }
+    writeln!(f, "+")
+}
+
+fn write_row(f: &mut Formatter<'_>, widths: &[usize], row: &[String]) -> std::fmt::Result {
+    for (i, width) in widths.iter().enumerate() {
+        let cell = row.get(i).map(String::as_str).unwrap_or_default();
+        write!(f, "| {:<width$} ", cell, width = width)?;
+    }
+    writeln!(f, "|")
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let widths = self.widths();
+
+        write_border(f, &widths)?;
+        write_row(f, &widths, &self.headers)?;
+        write_border(f, &widths)?;
+        for row in &self.rows {
+            write_row(f, &widths, row)?;
+        }
+        write_border(f, &widths)
+    }
+}
+
+/// Prints the rows as a table, see [Table].
+pub fn print_table(rows: &[DefaultSchema], headers: &[String]) {
+    print!("{}", Table::from_rows(headers, rows));
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{parser::FieldValue, table::Table, DefaultSchema};
+
+    #[test]
+    fn display_aligns_columns() {
+        let headers = vec!["name".to_string(), "height".to_string()];
+        let rows = vec![
+            DefaultSchema::new(vec![
+                Some(FieldValue::String("foo".into())),
+                Some(FieldValue::Float(0.5)),
+            ]),
+            DefaultSchema::new(vec![Some(FieldValue::String("barbaz".into())), None]),
+        ];
+
+        let table = Table::from_rows(&headers, &rows).to_string();
+
+        let expected = "\
++--------+--------+
+| name   | height |
++--------+--------+
+| foo    | 0.5    |
+| barbaz |        |
++--------+--------+
+";
+        assert_eq!(expected, table);
+    }
+
+    #[test]
+    fn display_pads_missing_cells() {
+        let table = Table::new(vec!["a".into()], vec![vec!["1".into(), "2".into()]]).to_string();
+
+        let expected = "\
++---+---+
+| a |   |
++---+---+
+| 1 | 2 |
++---+---+
+";
+        assert_eq!(expected, table);
+    }
+}
@@ -0,0 +1,427 @@
+use std::{
+    error::Error,
+    fs::{File, OpenOptions},
+    io::Write,
+    path::Path,
+};
+
+use crate::{
+    parser::{FieldParser, FieldValue, FloatParser, ParseContext},
+    ColumnOrder, CsvReader, DefaultSchema, COMMA,
+};
+
+/// The sequence of characters written at the end of each record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineTerminator {
+    /// `\n`
+    #[default]
+    Lf,
+    /// `\r\n`, often required by Windows consumers.
+    CrLf,
+}
+
+impl LineTerminator {
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match self {
+            LineTerminator::Lf => b"\n",
+            LineTerminator::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// Which fields are quoted, in addition to the fields that must be quoted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QuoteStyle {
+    /// Only the fields that contain the delimiter, the quote or a line break.
+    #[default]
+    Necessary,
+    /// Also the string values of [CsvWriter::write_row] that look numeric, such as the
+    /// ZIP code `01234`, so that a reader with [crate::parser::ParseContext::quoted_as_string]
+    /// reads them back as strings instead of numbers, keeping their leading zeros.
+    PreserveLeadingZeros,
+}
+
+/// Writes records as CSV.
+///
+/// Fields that contain the delimiter, the quote or a line break are quoted,
+/// and the quotes they contain are escaped by doubling them.
+pub struct CsvWriter<W: Write> {
+    inner: W,
+    delimiter: u8,
+    quote: u8,
+    terminator: LineTerminator,
+    trailing_terminator: bool,
+    quote_style: QuoteStyle,
+    records: usize,
+    skip_header: bool,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            delimiter: COMMA,
+            quote: b'"',
+            terminator: LineTerminator::default(),
+            trailing_terminator: true,
+            quote_style: QuoteStyle::default(),
+            records: 0,
+            skip_header: false,
+        }
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn with_line_terminator(mut self, terminator: LineTerminator) -> Self {
+        self.terminator = terminator;
+        self
+    }
+
+    /// Whether the last record is followed by a line terminator. Defaults to true.
+    pub fn with_trailing_terminator(mut self, trailing: bool) -> Self {
+        self.trailing_terminator = trailing;
+        self
+    }
+
+    pub fn with_quote_style(mut self, style: QuoteStyle) -> Self {
+        self.quote_style = style;
+        self
+    }
+
+    fn needs_quotes(&self, field: &[u8]) -> bool {
+        field
+            .iter()
+            .any(|b| *b == self.delimiter || *b == self.quote || *b == b'\n' || *b == b'\r')
+    }
+
+    fn write_field(&mut self, field: &[u8], force_quotes: bool) -> std::io::Result<()> {
+        if !force_quotes && !self.needs_quotes(field) {
+            return self.inner.write_all(field);
+        }
+
+        let quote = [self.quote];
+        self.inner.write_all(&quote)?;
+        for chunk in field.split_inclusive(|b| *b == self.quote) {
+            self.inner.write_all(chunk)?;
+            if chunk.last() == Some(&self.quote) {
+                self.inner.write_all(&quote)?;
+            }
+        }
+        self.inner.write_all(&quote)
+    }
+
+    /// Writes the header record, unless the writer appends to a file that already has
+    /// content, see [CsvWriter::append_to].
+    pub fn write_header<I, F>(&mut self, names: I) -> std::io::Result<()>
+    where
+        I: IntoIterator<Item = F>,
+        F: AsRef<[u8]>,
+    {
+        if self.skip_header {
+            return Ok(());
+        }
+        self.write_record(names)
+    }
+
+    /// Writes a record made of the fields.
+    pub fn write_record<I, F>(&mut self, fields: I) -> std::io::Result<()>
+    where
+        I: IntoIterator<Item = F>,
+        F: AsRef<[u8]>,
+    {
+        self.write_fields(fields.into_iter().map(|f| (f, false)))
+    }
+
+    /// Writes a row read by [crate::CsvReader::read_by_name] in the original order of the
+    /// columns: the fields, given in the order of the schema, are moved to their column,
+    /// and the other columns get back their unmapped values, so that only the edited
+    /// values differ from the source document. Write the header with
+    /// [ColumnOrder::names].
+    pub fn write_mapped<I, F>(
+        &mut self,
+        order: &ColumnOrder,
+        fields: I,
+        unmapped: &[String],
+    ) -> std::io::Result<()>
+    where
+        I: IntoIterator<Item = F>,
+        F: AsRef<[u8]>,
+    {
+        let columns = order.to_column_order(fields.into_iter().collect());
+        let mut unmapped = unmapped.iter();
+        let values: Vec<&[u8]> = columns
+            .iter()
+            .map(|column| match column {
+                Some(field) => field.as_ref(),
+                None => unmapped.next().map(String::as_bytes).unwrap_or_default(),
+            })
+            .collect();
+
+        self.write_fields(values.into_iter().map(|v| (v, false)))
+    }
+
+    /// Writes the fields, each with whether it must be quoted.
+    fn write_fields<F: AsRef<[u8]>>(
+        &mut self,
+        fields: impl Iterator<Item = (F, bool)>,
+    ) -> std::io::Result<()> {
+        // Without a trailing terminator, the terminator separates records instead of ending them.
+        if !self.trailing_terminator && self.records > 0 {
+            self.inner.write_all(self.terminator.as_bytes())?;
+        }
+
+        for (i, (field, force_quotes)) in fields.enumerate() {
+            if i > 0 {
+                self.inner.write_all(&[self.delimiter])?;
+            }
+            self.write_field(field.as_ref(), force_quotes)?;
+        }
+
+        if self.trailing_terminator {
+            self.inner.write_all(self.terminator.as_bytes())?;
+        }
+
+        self.records += 1;
+
+        Ok(())
+    }
+
+    /// Returns true if the value must be quoted because of the [QuoteStyle].
+    fn forces_quotes(&self, value: &FieldValue) -> bool {
+        match (self.quote_style, value) {
+            (QuoteStyle::PreserveLeadingZeros, FieldValue::String(s)) => {
+                FloatParser::<f64>::parse(s.as_bytes()).is_ok()
+            }
+            _ => false,
+        }
+    }
+
+    /// Writes a row, where empty fields are written as empty strings. Floats always have a
+    /// decimal point or an exponent, such as `10.0`, so that they are not read back as
+    /// integers.
+    pub fn write_row(&mut self, row: &DefaultSchema) -> std::io::Result<()> {
+        let fields: Vec<(String, bool)> = row
+            .fields()
+            .iter()
+            .map(|f| match f {
+                // The debug format of floats keeps `.0` for integral values.
+                Some(FieldValue::Float(v)) => (format!("{:?}", v), false),
+                Some(v) => (v.to_string(), self.forces_quotes(v)),
+                None => (String::new(), false),
+            })
+            .collect();
+
+        self.write_fields(fields.into_iter())
+    }
+
+    /// Reads the document with the context, then writes its header, if any, and its rows
+    /// with the settings of this writer. Values are written in their canonical form, such
+    /// as `1.5` for `1.50`.
+    pub fn write_normalized(
+        &mut self,
+        span: &[u8],
+        context: &ParseContext,
+    ) -> Result<(), Box<dyn Error>> {
+        let reader = CsvReader::<DefaultSchema>::default().with_context(context.clone());
+
+        if context.has_header() {
+            self.write_header(reader.read_headers(span))?;
+        }
+        for row in reader.read(span)? {
+            self.write_row(&row)?;
+        }
+
+        Ok(self.flush()?)
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+/// Reads the document with the context and writes it to `out` with the default settings
+/// of the [CsvWriter]: comma delimiter, `\n` line terminator and canonical values.
+/// See [CsvWriter::write_normalized] to choose the output settings.
+pub fn normalize(
+    span: &[u8],
+    out: impl Write,
+    context: &ParseContext,
+) -> Result<(), Box<dyn Error>> {
+    CsvWriter::new(out).write_normalized(span, context)
+}
+
+impl CsvWriter<File> {
+    /// Opens the file to append records to it, creating it if needed. If the file is not
+    /// empty, it is assumed to have a header already, and [CsvWriter::write_header]
+    /// writes nothing.
+    pub fn append_to(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        let non_empty = file.metadata()?.len() > 0;
+
+        let mut writer = Self::new(file);
+        writer.skip_header = non_empty;
+        Ok(writer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        parser::{FieldValue, ParseContext},
+        writer::{normalize, CsvWriter, LineTerminator, QuoteStyle},
+        CsvReader, DefaultSchema,
+    };
+
+    fn write(writer: CsvWriter<Vec<u8>>) -> String {
+        let mut writer = writer;
+        writer.write_record(["name", "height"]).unwrap();
+        writer.write_record(["foo", "0.5"]).unwrap();
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn write_lf() {
+        let output = write(CsvWriter::new(Vec::new()));
+
+        assert_eq!("name,height\nfoo,0.5\n", output);
+    }
+
+    #[test]
+    fn write_crlf() {
+        let output = write(CsvWriter::new(Vec::new()).with_line_terminator(LineTerminator::CrLf));
+
+        assert_eq!("name,height\r\nfoo,0.5\r\n", output);
+    }
+
+    #[test]
+    fn write_lf_without_trailing_terminator() {
+        let output = write(CsvWriter::new(Vec::new()).with_trailing_terminator(false));
+
+        assert_eq!("name,height\nfoo,0.5", output);
+    }
+
+    #[test]
+    fn write_crlf_without_trailing_terminator() {
+        let output = write(
+            CsvWriter::new(Vec::new())
+                .with_line_terminator(LineTerminator::CrLf)
+                .with_trailing_terminator(false),
+        );
+
+        assert_eq!("name,height\r\nfoo,0.5", output);
+    }
+
+    #[test]
+    fn append_to_skips_header_of_non_empty_file() {
+        let path = std::env::temp_dir().join(format!("append-{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        for (name, height) in [("foo", "0.5"), ("bar", "1.5")] {
+            let mut writer = CsvWriter::append_to(&path).unwrap();
+            writer.write_header(["name", "height"]).unwrap();
+            writer.write_record([name, height]).unwrap();
+            writer.flush().unwrap();
+        }
+
+        let output = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!("name,height\nfoo,0.5\nbar,1.5\n", output);
+    }
+
+    #[test]
+    fn normalize_round_trip_with_delimiter_change() {
+        let context = ParseContext::builder().delimiter(b';').build().unwrap();
+        let csv = b"name;height;count\r\n\"Smith, John\";1.50;03\r\nfoo;;1e1\r\n";
+        let mut output = Vec::new();
+
+        normalize(csv, &mut output, &context).unwrap();
+
+        assert_eq!(
+            "name,height,count\n\"Smith, John\",1.5,3\nfoo,,10.0\n",
+            String::from_utf8(output.clone()).unwrap()
+        );
+
+        let original = CsvReader::<DefaultSchema>::default().with_context(context);
+        let normalized = CsvReader::<DefaultSchema>::default();
+        assert_eq!(
+            original.read(csv).unwrap(),
+            normalized.read(&output).unwrap()
+        );
+    }
+
+    #[test]
+    fn write_normalized_with_writer_settings() {
+        let mut writer = CsvWriter::new(Vec::new()).with_delimiter(b'\t');
+
+        writer
+            .write_normalized(b"a,b\n1,x\n", &ParseContext::default())
+            .unwrap();
+
+        assert_eq!(b"a\tb\n1\tx\n".to_vec(), writer.into_inner());
+    }
+
+    #[test]
+    fn write_quotes_fields_when_needed() {
+        let mut writer = CsvWriter::new(Vec::new());
+        writer
+            .write_record(["Smith, John", "say \"hi\"", "plain"])
+            .unwrap();
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+
+        assert_eq!("\"Smith, John\",\"say \"\"hi\"\"\",plain\n", output);
+    }
+
+    #[test]
+    fn write_row() {
+        let mut writer = CsvWriter::new(Vec::new()).with_delimiter(b';');
+        writer
+            .write_row(&DefaultSchema::new(vec![
+                Some(FieldValue::String("a".into())),
+                None,
+                Some(FieldValue::Float(1.5)),
+            ]))
+            .unwrap();
+
+        let output = String::from_utf8(writer.into_inner()).unwrap();
+
+        assert_eq!("a;;1.5\n", output);
+    }
+
+    #[test]
+    fn preserve_leading_zeros_round_trip() {
+        let zip = FieldValue::String("01234".into());
+        let mut writer =
+            CsvWriter::new(Vec::new()).with_quote_style(QuoteStyle::PreserveLeadingZeros);
+        writer.write_record(["zip", "height"]).unwrap();
+        writer
+            .write_row(&DefaultSchema::new(vec![
+                Some(zip.clone()),
+                Some(FieldValue::Float(1.5)),
+            ]))
+            .unwrap();
+
+        let output = writer.into_inner();
+        assert_eq!(b"zip,height\n\"01234\",1.5\n".to_vec(), output);
+
+        let context = ParseContext::builder()
+            .quoted_as_string(true)
+            .build()
+            .unwrap();
+        let rows = CsvReader::<DefaultSchema>::default()
+            .with_context(context)
+            .read(&output)
+            .unwrap();
+
+        assert_eq!(Some(zip), rows[0].fields()[0]);
+        assert_eq!(Some(FieldValue::Float(1.5)), rows[0].fields()[1]);
+    }
+}
@@ -0,0 +1,79 @@
+//! Counts the allocations of the readers. The counting allocator replaces the allocator of
+//! the whole test binary, so it lives in its own target.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use rust_csv_reader::{CsvReader, DefaultSchema};
+
+/// Counts the allocations of the current thread, so that tests running in parallel don't
+/// add to each other's count.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|a| a.set(a.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    let count = ALLOCATIONS.with(Cell::get) - before;
+    drop(result);
+    count
+}
+
+rust_csv_reader::schema!(Person<'a>, name:&'a str, city:&'a str, code:&'a str);
+rust_csv_reader::schema!(OwnedPerson, name:String, city:String, code:String);
+
+fn document() -> String {
+    "name,city,code\n".to_string() + &"foo,bar,baz\n".repeat(100)
+}
+
+#[test]
+fn borrowed_schema_allocates_less_than_owned_schema() {
+    let csv = document();
+
+    let borrowed = allocations(|| {
+        CsvReader::<Person>::default()
+            .read_borrowed(csv.as_bytes())
+            .unwrap()
+    });
+    let owned = allocations(|| {
+        CsvReader::<OwnedPerson>::default()
+            .read(csv.as_bytes())
+            .unwrap()
+    });
+
+    // A few allocations for the result, against one per field.
+    assert!(borrowed < 50, "{}", borrowed);
+    assert!(owned >= 300, "{}", owned);
+}
+
+#[test]
+fn str_fields_allocate_less_than_owned_strings() {
+    let csv = document();
+    let reader = CsvReader::<DefaultSchema>::default();
+
+    let borrowed = allocations(|| reader.read_str_fields(csv.as_bytes()).unwrap());
+    let owned = allocations(|| reader.read_raw_strings(csv.as_bytes()));
+
+    // About one allocation per row, against one more per field.
+    assert!(borrowed < 150, "{}", borrowed);
+    assert!(owned >= 400, "{}", owned);
+}
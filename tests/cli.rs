@@ -0,0 +1,47 @@
+#![cfg(feature = "serde")]
+
+use std::{path::PathBuf, process::Command};
+
+/// Writes the document to a temp file of a name unique to the test and the process.
+fn fixture(name: &str, contents: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("cli-{}-{}.csv", name, std::process::id()));
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_mybin"))
+        .args(args)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn format_json() {
+    let path = fixture("format-json", "name,height\nfoo,1.5\nbar,2\n");
+
+    let stdout = run(&["--format", "json", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(
+        serde_json::json!([
+            {"name": "foo", "height": 1.5},
+            {"name": "bar", "height": 2},
+        ]),
+        json
+    );
+}
+
+#[test]
+fn quiet_prints_nothing() {
+    let path = fixture("quiet", "name,height\nfoo,1.5\n");
+
+    let stdout = run(&["--quiet", "--format", "json", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!("", stdout);
+}